@@ -26,6 +26,9 @@ use mousefood::prelude::*;
 use ratatui::widgets::{Block, Paragraph, Wrap};
 use ratatui::{Frame, Terminal, style::*};
 
+/// Returned from the flush callback when the simulator window is closed.
+struct WindowClosed;
+
 fn main() -> Result<(), Error> {
     // Create window where the simulation will happen
     let mut simulator_window = Window::new(
@@ -45,8 +48,9 @@ fn main() -> Result<(), Error> {
         flush_callback: Box::new(move |display| {
             simulator_window.update(display);
             if simulator_window.events().any(|e| e == SimulatorEvent::Quit) {
-                panic!("simulator window closed");
+                return Err(DrawTargetError::new(WindowClosed));
             }
+            Ok(())
         }),
         ..Default::default()
     };
@@ -56,9 +60,13 @@ fn main() -> Result<(), Error> {
     // Start ratatui with our simulator backend
     let mut terminal = Terminal::new(backend)?;
 
-    // Run an infinite loop, where widgets will be rendered
+    // Run a loop, where widgets will be rendered, until the window is closed
     loop {
-        terminal.draw(draw)?;
+        match terminal.draw(draw) {
+            Ok(_) => {}
+            Err(Error::FlushError(error)) if error.is::<WindowClosed>() => return Ok(()),
+            Err(error) => return Err(error),
+        }
     }
 }
 
@@ -0,0 +1,249 @@
+//! Parser of the Glyph Bitmap Distribution Format.
+
+use crate::{Error, Font};
+
+/// Bounding box of a font or glyph, as in the `FONTBOUNDINGBOX` and `BBX` keywords.
+#[derive(Clone, Copy, Debug, Default)]
+struct BoundingBox {
+    width: i32,
+    height: i32,
+    x: i32,
+    y: i32,
+}
+
+/// Glyph being parsed.
+#[derive(Default)]
+struct Glyph {
+    encoding: Option<char>,
+    bounding_box: Option<BoundingBox>,
+    rows: Vec<Vec<u8>>,
+}
+
+pub(crate) fn parse(source: &str) -> Result<Font, Error> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    let mut font_box = None;
+    let mut ascent = None;
+    let mut descent = None;
+    let mut default_char = None;
+    let mut glyphs = Vec::new();
+
+    while let Some((line, text)) = lines.next() {
+        let error = |message: &str| Error::InvalidBdf {
+            line,
+            message: message.into(),
+        };
+        let mut words = text.split_whitespace();
+        match words.next() {
+            Some("FONTBOUNDINGBOX") => {
+                font_box =
+                    Some(bounding_box(words).ok_or_else(|| error("malformed FONTBOUNDINGBOX"))?)
+            }
+            Some("FONT_ASCENT") => {
+                ascent = Some(number(words.next()).ok_or_else(|| error("malformed FONT_ASCENT"))?)
+            }
+            Some("FONT_DESCENT") => {
+                descent = Some(number(words.next()).ok_or_else(|| error("malformed FONT_DESCENT"))?)
+            }
+            Some("DEFAULT_CHAR") => {
+                default_char = number(words.next()).and_then(|code| char::from_u32(code as u32))
+            }
+            Some("STARTCHAR") => glyphs.push(parse_glyph(&mut lines, line)?),
+            _ => {}
+        }
+    }
+
+    let font_box = font_box.ok_or(Error::InvalidBdf {
+        line: 1,
+        message: "missing FONTBOUNDINGBOX".into(),
+    })?;
+    let ascent = ascent.unwrap_or(font_box.height + font_box.y).max(0);
+    let descent = descent.unwrap_or(-font_box.y).max(0);
+
+    let mut font = Font::new(
+        font_box.width.max(0) as u32,
+        (ascent + descent) as u32,
+        ascent as u32,
+    );
+    for glyph in glyphs {
+        let (Some(c), Some(glyph_box)) = (glyph.encoding, glyph.bounding_box) else {
+            continue;
+        };
+        let mut bitmap = vec![false; (font.width * font.height) as usize];
+        for (row, bytes) in glyph.rows.iter().enumerate() {
+            for column in 0..glyph_box.width {
+                let set = bytes
+                    .get(column as usize / 8)
+                    .is_some_and(|byte| byte & (0x80 >> (column % 8)) != 0);
+                let x = glyph_box.x - font_box.x + column;
+                let y = ascent - glyph_box.y - glyph_box.height + row as i32;
+                if set
+                    && (0..font.width as i32).contains(&x)
+                    && (0..font.height as i32).contains(&y)
+                {
+                    bitmap[(y as u32 * font.width + x as u32) as usize] = true;
+                }
+            }
+        }
+        font.glyphs.insert(c, bitmap);
+    }
+    font.default_char = default_char.filter(|c| font.contains(*c));
+    Ok(font)
+}
+
+/// Parses the glyph following the `STARTCHAR` keyword at `start`, up to `ENDCHAR`.
+fn parse_glyph<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    start: usize,
+) -> Result<Glyph, Error> {
+    let mut glyph = Glyph::default();
+    let mut in_bitmap = false;
+    for (line, text) in lines {
+        let error = |message: &str| Error::InvalidBdf {
+            line,
+            message: message.into(),
+        };
+        let mut words = text.split_whitespace();
+        match words.next() {
+            Some("ENDCHAR") => return Ok(glyph),
+            Some("ENCODING") => {
+                let code = number(words.next()).ok_or_else(|| error("malformed ENCODING"))?;
+                // Negative encodings mark glyphs outside of the font's encoding.
+                glyph.encoding = u32::try_from(code).ok().and_then(char::from_u32);
+            }
+            Some("BBX") => {
+                glyph.bounding_box =
+                    Some(bounding_box(words).ok_or_else(|| error("malformed BBX"))?);
+            }
+            Some("BITMAP") => in_bitmap = true,
+            Some(hex) if in_bitmap => {
+                let row = (0..hex.len() / 2)
+                    .map(|index| u8::from_str_radix(hex.get(2 * index..2 * index + 2)?, 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| error("malformed BITMAP row"))?;
+                glyph.rows.push(row);
+            }
+            _ => {}
+        }
+    }
+    Err(Error::InvalidBdf {
+        line: start,
+        message: "STARTCHAR without ENDCHAR".into(),
+    })
+}
+
+fn number(word: Option<&str>) -> Option<i32> {
+    word?.parse().ok()
+}
+
+fn bounding_box<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<BoundingBox> {
+    Some(BoundingBox {
+        width: number(words.next())?,
+        height: number(words.next())?,
+        x: number(words.next())?,
+        y: number(words.next())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const FONT: &str = "\
+STARTFONT 2.1
+FONT -test-fixed-medium-r-normal--6-60-75-75-c-40-iso10646-1
+SIZE 6 75 75
+FONTBOUNDINGBOX 4 6 0 -1
+STARTPROPERTIES 3
+FONT_ASCENT 5
+FONT_DESCENT 1
+DEFAULT_CHAR 63
+ENDPROPERTIES
+CHARS 3
+STARTCHAR question
+ENCODING 63
+SWIDTH 666 0
+DWIDTH 4 0
+BBX 3 5 0 0
+BITMAP
+E0
+20
+40
+00
+40
+ENDCHAR
+STARTCHAR j
+ENCODING 106
+SWIDTH 666 0
+DWIDTH 4 0
+BBX 2 5 1 -1
+BITMAP
+40
+00
+40
+40
+80
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1 200
+BBX 4 6 0 -1
+BITMAP
+F0
+F0
+F0
+F0
+F0
+F0
+ENDCHAR
+ENDFONT
+";
+
+    #[rstest]
+    fn parses_font() {
+        let font = parse(FONT).unwrap();
+        assert_eq!(font.character_size(), (4, 6));
+        assert_eq!(font.ascent(), 5);
+        assert_eq!(font.default_char, Some('?'));
+        assert_eq!(font.chars().collect::<Vec<_>>(), ['?', 'j']);
+    }
+
+    #[rstest]
+    #[case('?', &["###.", "..#.", ".#..", "....", ".#..", "...."])]
+    #[case('j', &["....", "..#.", "....", "..#.", "..#.", ".#.."])]
+    fn places_glyphs_on_baseline(#[case] c: char, #[case] expected: &[&str]) {
+        let font = parse(FONT).unwrap();
+        let rows: Vec<String> = (0..6)
+            .map(|y| {
+                (0..4)
+                    .map(|x| {
+                        if font.pixel(c, x, y).unwrap() {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, expected);
+    }
+
+    #[rstest]
+    #[case("STARTFONT 2.1\nENDFONT\n", 1)]
+    #[case("FONTBOUNDINGBOX 4 6 0\n", 1)]
+    #[case(
+        "FONTBOUNDINGBOX 4 6 0 0\nSTARTCHAR a\nENCODING 97\nBITMAP\nZZ\nENDCHAR\n",
+        5
+    )]
+    #[case("FONTBOUNDINGBOX 4 6 0 0\nSTARTCHAR a\nENCODING 97\n", 2)]
+    fn reports_malformed_font(#[case] source: &str, #[case] expected_line: usize) {
+        match parse(source) {
+            Err(Error::InvalidBdf { line, .. }) => assert_eq!(line, expected_line),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+}
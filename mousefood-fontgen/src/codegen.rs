@@ -0,0 +1,196 @@
+//! Generation of `MonoFont` source code.
+
+use crate::{Error, Font};
+
+/// Number of glyphs in each row of the generated image.
+const GLYPHS_PER_ROW: u32 = 16;
+
+/// Returns the index of the glyph of the default character of the font,
+/// or of a common replacement character.
+fn replacement(font: &Font) -> Option<usize> {
+    font.default_char
+        .or(['\u{FFFD}', '?'].into_iter().find(|c| font.contains(*c)))
+        .and_then(|c| font.chars().position(|glyph| glyph == c))
+}
+
+/// Number of glyphs in the image.
+///
+/// Fonts without a replacement character get a blank replacement glyph after their glyphs,
+/// so that missing characters aren't drawn with the glyph of a regular character,
+/// which mousefood would consider missing too.
+fn glyph_count(font: &Font) -> usize {
+    font.glyphs.len() + replacement(font).is_none() as usize
+}
+
+/// Width of the glyph image in pixels.
+pub(crate) fn image_width(font: &Font) -> u32 {
+    font.width * GLYPHS_PER_ROW.min(glyph_count(font) as u32).max(1)
+}
+
+/// Packs the glyphs into a 1 bit per pixel image, in the order of their characters,
+/// as expected by `ImageRaw<BinaryColor>`.
+pub(crate) fn image(font: &Font) -> Result<Vec<u8>, Error> {
+    if font.glyphs.is_empty() || font.width == 0 || font.height == 0 {
+        return Err(Error::Empty);
+    }
+    let width = image_width(font);
+    let columns = width / font.width;
+    let rows = (glyph_count(font) as u32).div_ceil(columns);
+    let row_bytes = width.div_ceil(8) as usize;
+
+    let mut image = vec![0; row_bytes * (rows * font.height) as usize];
+    for (index, glyph) in font.glyphs.values().enumerate() {
+        let left = index as u32 % columns * font.width;
+        let top = index as u32 / columns * font.height;
+        for y in 0..font.height {
+            for x in 0..font.width {
+                if glyph[(y * font.width + x) as usize] {
+                    let column = (left + x) as usize;
+                    image[(top + y) as usize * row_bytes + column / 8] |= 0x80 >> (column % 8);
+                }
+            }
+        }
+    }
+    Ok(image)
+}
+
+/// Returns the `StrGlyphMapping` string for `chars`, given in ascending order.
+///
+/// Runs of at least three consecutive characters are written as ranges.
+pub(crate) fn mapping(chars: impl IntoIterator<Item = char>) -> String {
+    let mut runs: Vec<(char, char)> = Vec::new();
+    for c in chars {
+        match runs.last_mut() {
+            Some((_, end)) if *end as u32 + 1 == c as u32 => *end = c,
+            _ => runs.push((c, c)),
+        }
+    }
+
+    let mut mapping = String::new();
+    for (start, end) in runs {
+        // A single NUL would start a range, so it is always written as one.
+        if end as u32 - start as u32 >= 2 || start == '\0' {
+            mapping.extend(['\0', start, end]);
+        } else {
+            mapping.extend(start..=end);
+        }
+    }
+    mapping
+}
+
+/// Generates the source of the `name` constant, with `data` as the glyph image expression.
+pub(crate) fn source(font: &Font, name: &str, data: &str, image_width: u32) -> String {
+    let eg = &font.embedded_graphics_path;
+    let replacement = replacement(font).unwrap_or(font.glyphs.len());
+    // The baseline of `MonoFont` is the last row above it.
+    let baseline = font.ascent.saturating_sub(1);
+    let underline = (font.ascent + 1).min(font.height.saturating_sub(1));
+    let strikethrough = font.height.saturating_sub(1) / 2;
+
+    format!(
+        "\
+// Generated by mousefood-fontgen. Do not edit.
+
+#[allow(missing_docs)]
+pub const {name}: {eg}::mono_font::MonoFont<'static> = {eg}::mono_font::MonoFont {{
+    image: {eg}::image::ImageRaw::new({data}, {image_width}u32),
+    glyph_mapping: &{eg}::mono_font::mapping::StrGlyphMapping::new({mapping:?}, {replacement}usize),
+    character_size: {eg}::geometry::Size::new({width}u32, {height}u32),
+    character_spacing: 0u32,
+    baseline: {baseline}u32,
+    underline: {eg}::mono_font::DecorationDimensions::new({underline}u32, 1u32),
+    strikethrough: {eg}::mono_font::DecorationDimensions::new({strikethrough}u32, 1u32),
+}};
+",
+        mapping = mapping(font.chars()),
+        width = font.width,
+        height = font.height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("a", "a")]
+    #[case("ab", "ab")]
+    #[case("abc", "\0ac")]
+    #[case("abcxyz", "\0ac\0xz")]
+    #[case("abdf", "abdf")]
+    #[case(" !\"#", "\0 #")]
+    #[case("\0a", "\0\0\0a")]
+    fn compresses_mapping(#[case] chars: &str, #[case] expected: &str) {
+        assert_eq!(mapping(chars.chars()), expected);
+    }
+
+    fn font(glyphs: usize) -> Font {
+        let mut font = Font::new(3, 2, 1);
+        for index in 0..glyphs {
+            let mut bitmap = vec![false; 6];
+            bitmap[index % 6] = true;
+            font.glyphs.insert(char::from(b'a' + index as u8), bitmap);
+        }
+        font
+    }
+
+    #[rstest]
+    #[case(1, 6, &[0b1000_0000, 0])]
+    #[case(2, 9, &[0b1000_1000, 0, 0, 0])]
+    #[case(17, 48, &[
+        0x88, 0x80, 0x22, 0x20, 0x08, 0x88,
+        0x00, 0x44, 0x40, 0x11, 0x10, 0x04,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x40, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ])]
+    fn packs_image(#[case] glyphs: usize, #[case] width: u32, #[case] expected: &[u8]) {
+        let font = font(glyphs);
+        assert_eq!(image_width(&font), width);
+        assert_eq!(image(&font).unwrap(), expected);
+    }
+
+    #[rstest]
+    fn rejects_empty_font() {
+        assert!(matches!(image(&font(0)), Err(Error::Empty)));
+    }
+
+    #[rstest]
+    fn generates_source() {
+        let mut font = font(3);
+        font.set_embedded_graphics_path("::embedded_graphics");
+        let source = source(&font, "MY_FONT", "&[0]", 9);
+
+        assert!(
+            source.contains("pub const MY_FONT: ::embedded_graphics::mono_font::MonoFont<'static>")
+        );
+        assert!(source.contains("ImageRaw::new(&[0], 9u32)"));
+        assert!(source.contains(r#"StrGlyphMapping::new("\0ac", 3usize)"#));
+        assert!(source.contains("Size::new(3u32, 2u32)"));
+        assert!(source.contains("baseline: 0u32"));
+    }
+
+    #[rstest]
+    #[case(None, None, 3)]
+    #[case(None, Some('?'), 0)]
+    #[case(Some('b'), Some('?'), 2)]
+    fn replacement_glyph_is_not_aliased(
+        #[case] default_char: Option<char>,
+        #[case] extra: Option<char>,
+        #[case] expected: usize,
+    ) {
+        let mut font = font(3);
+        if let Some(c) = extra {
+            font.glyphs.insert(c, vec![false; 6]);
+        }
+        font.default_char = default_char;
+
+        let source = source(&font, "MY_FONT", "&[0]", 12);
+        assert!(source.contains(&format!(", {expected}usize)")));
+        // Fonts without a replacement character get a blank glyph after those of `abc`.
+        assert_eq!(glyph_count(&font), 4);
+        assert_eq!(image_width(&font), 12);
+        assert_eq!(image(&font).unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,30 @@
+//! Font conversion `Error` enum.
+
+/// Represents font conversion error.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Reading the font or writing the generated files failed.
+    #[error("font file I/O failed")]
+    Io(#[from] std::io::Error),
+
+    /// The BDF font is malformed.
+    #[error("invalid BDF font at line {line}: {message}")]
+    InvalidBdf {
+        /// Line of the font source, starting at 1.
+        line: usize,
+        /// Description of the problem.
+        message: String,
+    },
+
+    /// The PCF font is malformed.
+    #[error("invalid PCF font: {0}")]
+    InvalidPcf(String),
+
+    /// A code point list has a malformed entry.
+    #[error("invalid code point: {0}")]
+    InvalidCodePoint(String),
+
+    /// The font has no glyphs to generate.
+    #[error("font has no glyphs")]
+    Empty,
+}
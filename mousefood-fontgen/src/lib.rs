@@ -0,0 +1,203 @@
+//! Build-time conversion of BDF and PCF bitmap fonts into
+//! [`MonoFont`](https://docs.rs/embedded-graphics/latest/embedded_graphics/mono_font/struct.MonoFont.html)s
+//! for [mousefood](https://crates.io/crates/mousefood).
+//!
+//! Fonts are loaded with [`Font::open`], optionally reduced to the characters
+//! an application uses, and written as Rust source from a build script:
+//!
+//! ```no_run
+//! // build.rs
+//! use mousefood_fontgen::{Font, Subset, SymbolSet};
+//!
+//! fn main() -> Result<(), mousefood_fontgen::Error> {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     println!("cargo::rerun-if-changed=fonts/terminus-8x16.bdf");
+//!     println!("cargo::rerun-if-changed=src");
+//!
+//!     let mut subset = Subset::new();
+//!     subset.add_symbols(SymbolSet::Ascii);
+//!     subset.add_sources("src")?;
+//!
+//!     let mut font = Font::open("fonts/terminus-8x16.bdf")?;
+//!     font.subset(&subset);
+//!     font.write("TERMINUS_8X16", out_dir)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Existing `MonoFont`s can be read with [`Font::from_mono_font`] instead.
+//!
+//! The generated file defines a `MonoFont<'static>` constant,
+//! ready for `EmbeddedBackendConfig::font_regular` or the other font slots:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/terminus_8x16.rs"));
+//!
+//! let config = EmbeddedBackendConfig {
+//!     font_regular: TERMINUS_8X16.into(),
+//!     ..Default::default()
+//! };
+//! ```
+
+mod bdf;
+mod codegen;
+mod error;
+mod mono;
+mod pcf;
+mod subset;
+
+pub use error::Error;
+pub use subset::{Subset, SymbolSet};
+
+use embedded_graphics::mono_font::MonoFont;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of PCF files.
+const PCF_MAGIC: &[u8] = b"\x01fcp";
+
+/// A monospaced bitmap font.
+///
+/// Every glyph is stored as a bitmap of the font's character size,
+/// with the glyph placed relative to the baseline of the font.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Font {
+    width: u32,
+    height: u32,
+    ascent: u32,
+    default_char: Option<char>,
+    glyphs: BTreeMap<char, Vec<bool>>,
+    embedded_graphics_path: String,
+}
+
+impl Font {
+    /// Loads a BDF or PCF font from `path`.
+    ///
+    /// The format is detected from the content of the file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let data = std::fs::read(path)?;
+        if data.starts_with(PCF_MAGIC) {
+            Self::from_pcf(&data)
+        } else {
+            let source = String::from_utf8_lossy(&data);
+            Self::from_bdf(&source)
+        }
+    }
+
+    /// Parses a font in the Glyph Bitmap Distribution Format.
+    ///
+    /// Glyph encodings are taken as Unicode code points,
+    /// so the font should use the `ISO10646` (or `ISO8859-1`) registry.
+    pub fn from_bdf(source: &str) -> Result<Self, Error> {
+        bdf::parse(source)
+    }
+
+    /// Parses a font in the Portable Compiled Format.
+    ///
+    /// Glyph encodings are taken as Unicode code points,
+    /// so the font should use the `ISO10646` (or `ISO8859-1`) registry.
+    pub fn from_pcf(data: &[u8]) -> Result<Self, Error> {
+        pcf::parse(data)
+    }
+
+    /// Reads the glyphs of `chars` from an embedded-graphics `MonoFont`,
+    /// such as the fonts of `embedded-graphics-unicodefonts`.
+    ///
+    /// Characters drawn with the replacement glyph of the font are left out,
+    /// except `?` and `U+FFFD` when the replacement glyph depicts them.
+    /// Use [`Subset::chars`] to read the characters of a subset.
+    pub fn from_mono_font(font: &MonoFont<'_>, chars: impl IntoIterator<Item = char>) -> Self {
+        mono::read(font, chars)
+    }
+
+    /// Creates an empty font of the given character size and ascent.
+    pub(crate) fn new(width: u32, height: u32, ascent: u32) -> Self {
+        Self {
+            width,
+            height,
+            ascent,
+            default_char: None,
+            glyphs: BTreeMap::new(),
+            embedded_graphics_path: String::from("::mousefood::embedded_graphics"),
+        }
+    }
+
+    /// Size of the characters in pixels, as `(width, height)`.
+    pub fn character_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Height of the characters above the baseline, in pixels.
+    pub fn ascent(&self) -> u32 {
+        self.ascent
+    }
+
+    /// Returns an iterator over the characters the font has glyphs for, in ascending order.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.glyphs.keys().copied()
+    }
+
+    /// Returns whether the font has a glyph for `c`.
+    pub fn contains(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// Returns the pixel at column `x` and row `y` of the glyph for `c`.
+    pub fn pixel(&self, c: char, x: u32, y: u32) -> Option<bool> {
+        let glyph = self.glyphs.get(&c)?;
+        (x < self.width && y < self.height).then(|| glyph[(y * self.width + x) as usize])
+    }
+
+    /// Keeps only the glyphs of characters for which `f` returns `true`.
+    ///
+    /// The glyph of the font's default character, used in place of missing characters,
+    /// is always kept.
+    pub fn retain(&mut self, mut f: impl FnMut(char) -> bool) {
+        let default_char = self.default_char;
+        self.glyphs.retain(|&c, _| f(c) || Some(c) == default_char);
+    }
+
+    /// Keeps only the glyphs of characters in `subset`, and the default character.
+    pub fn subset(&mut self, subset: &Subset) {
+        self.retain(|c| subset.contains(c));
+    }
+
+    /// Sets the path of the `embedded-graphics` crate used by the generated code.
+    ///
+    /// Defaults to `::mousefood::embedded_graphics`, the re-export of mousefood.
+    pub fn set_embedded_graphics_path(&mut self, path: impl Into<String>) {
+        self.embedded_graphics_path = path.into();
+    }
+
+    /// Generates Rust source defining the font as a `MonoFont<'static>` constant named `name`.
+    ///
+    /// The glyph bitmaps are embedded as a byte array literal.
+    /// For large fonts, prefer [`write`](Self::write), which stores them in a separate file.
+    pub fn to_rust(&self, name: &str) -> Result<String, Error> {
+        let image = codegen::image(self)?;
+        Ok(codegen::source(
+            self,
+            name,
+            &format!("&{image:?}"),
+            codegen::image_width(self),
+        ))
+    }
+
+    /// Writes the font as a `MonoFont<'static>` constant named `name` into `dir`.
+    ///
+    /// The source is written to `<name>.rs` and the glyph bitmaps to `<name>.data`,
+    /// with `name` converted to lowercase. Returns the path of the source file,
+    /// which can be included with [`include!`].
+    pub fn write(&self, name: &str, dir: impl AsRef<Path>) -> Result<PathBuf, Error> {
+        let image = codegen::image(self)?;
+        let file_name = name.to_lowercase();
+        let data_path = dir.as_ref().join(format!("{file_name}.data"));
+        let source_path = dir.as_ref().join(format!("{file_name}.rs"));
+
+        std::fs::write(&data_path, image)?;
+        let data = format!("include_bytes!({:?})", data_path.display().to_string());
+        let source = codegen::source(self, name, &data, codegen::image_width(self));
+        std::fs::write(&source_path, source)?;
+        Ok(source_path)
+    }
+}
@@ -0,0 +1,104 @@
+//! Reader of embedded-graphics `MonoFont`s.
+
+use embedded_graphics::geometry::{OriginDimensions, Point};
+use embedded_graphics::image::GetPixel;
+use embedded_graphics::mono_font::MonoFont;
+use embedded_graphics::pixelcolor::BinaryColor;
+
+use crate::Font;
+
+/// Characters drawn in place of missing characters by common fonts.
+const REPLACEMENT_CHARS: [char; 2] = ['\u{FFFD}', '?'];
+
+pub(crate) fn read(mono: &MonoFont<'_>, chars: impl IntoIterator<Item = char>) -> Font {
+    let size = mono.character_size;
+    let mut font = Font::new(size.width, size.height, mono.baseline + 1);
+
+    // Noncharacters are never in a font, so both map to the replacement glyph.
+    let replacement = mono.glyph_mapping.index('\u{FFFF}');
+    let replacement = (replacement == mono.glyph_mapping.index('\u{FFFE}')).then_some(replacement);
+
+    for c in chars {
+        let index = mono.glyph_mapping.index(c);
+        let glyph = glyph(mono, index);
+        if Some(index) == replacement {
+            // The replacement glyph is only kept for the character it depicts.
+            if !REPLACEMENT_CHARS.contains(&c) || !glyph.contains(&true) {
+                continue;
+            }
+            font.default_char = Some(c);
+        }
+        font.glyphs.insert(c, glyph);
+    }
+    font
+}
+
+/// Reads the bitmap of the glyph at `index` in the image of the font.
+fn glyph(mono: &MonoFont<'_>, index: usize) -> Vec<bool> {
+    let size = mono.character_size;
+    let glyphs_per_row = (mono.image.size().width / size.width.max(1)).max(1);
+    let left = (index as u32 % glyphs_per_row * size.width) as i32;
+    let top = (index as u32 / glyphs_per_row * size.height) as i32;
+
+    (0..size.height as i32)
+        .flat_map(|y| (0..size.width as i32).map(move |x| (x, y)))
+        .map(|(x, y)| mono.image.pixel(Point::new(left + x, top + y)) == Some(BinaryColor::On))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+    use embedded_graphics::mono_font::{MonoTextStyle, mapping};
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::text::{Baseline, Text};
+    use rstest::rstest;
+
+    /// Draws `c` with `mono` and returns the pixels that are on.
+    fn draw(mono: &MonoFont<'_>, c: char) -> Vec<bool> {
+        let mut display = embedded_graphics::mock_display::MockDisplay::new();
+        let style = MonoTextStyle::new(mono, BinaryColor::On);
+        let mut text = [0; 4];
+        Text::with_baseline(
+            c.encode_utf8(&mut text),
+            Point::zero(),
+            style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        let size = mono.character_size;
+        (0..size.height as i32)
+            .flat_map(|y| (0..size.width as i32).map(move |x| Point::new(x, y)))
+            .map(|point| display.get_pixel(point) == Some(BinaryColor::On))
+            .collect()
+    }
+
+    #[rstest]
+    fn reads_glyphs() {
+        let font = read(&FONT_6X10, "aZ~?é".chars());
+
+        assert_eq!(font.character_size(), (6, 10));
+        assert_eq!(font.ascent(), 8);
+        assert_eq!(font.chars().collect::<String>(), "?Za~");
+        assert_eq!(font.default_char, Some('?'));
+        for c in font.chars() {
+            assert_eq!(font.glyphs[&c], draw(&FONT_6X10, c), "glyph of {c:?}");
+        }
+    }
+
+    #[rstest]
+    fn skips_blank_replacement_glyph() {
+        const FONT: MonoFont = MonoFont {
+            glyph_mapping: &mapping::StrGlyphMapping::new(" ab", 0),
+            ..FONT_6X10
+        };
+        // The blank glyph of the space is the replacement glyph.
+        let font = read(&FONT, " ab?".chars());
+
+        assert_eq!(font.chars().collect::<String>(), "ab");
+        assert_eq!(font.default_char, None);
+    }
+}
@@ -0,0 +1,407 @@
+//! Parser of the Portable Compiled Format.
+
+use crate::{Error, Font, PCF_MAGIC};
+
+const PCF_ACCELERATORS: u32 = 1 << 1;
+const PCF_METRICS: u32 = 1 << 2;
+const PCF_BITMAPS: u32 = 1 << 3;
+const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+const PCF_BDF_ACCELERATORS: u32 = 1 << 8;
+
+const PCF_GLYPH_PAD_MASK: u32 = 3;
+const PCF_BYTE_MASK: u32 = 1 << 2;
+const PCF_BIT_MASK: u32 = 1 << 3;
+const PCF_SCAN_UNIT_SHIFT: u32 = 4;
+const PCF_COMPRESSED_METRICS: u32 = 0x100;
+
+/// Encoding index of code points without a glyph.
+const NO_GLYPH: u16 = 0xFFFF;
+
+/// Metrics of a single glyph.
+#[derive(Clone, Copy, Debug)]
+struct Metrics {
+    left_bearing: i32,
+    right_bearing: i32,
+    width: i32,
+    ascent: i32,
+    descent: i32,
+}
+
+/// Reader of a table, in the byte order given by its format.
+struct Table<'a> {
+    data: &'a [u8],
+    position: usize,
+    format: u32,
+}
+
+impl<'a> Table<'a> {
+    /// Finds the table of `kind` in the table of contents.
+    fn find(data: &'a [u8], kind: u32) -> Result<Option<Self>, Error> {
+        let mut header = Table {
+            data,
+            position: PCF_MAGIC.len(),
+            format: 0,
+        };
+        let count = header.u32()?;
+        for _ in 0..count {
+            let (table_kind, _format, size, offset) =
+                (header.u32()?, header.u32()?, header.u32()?, header.u32()?);
+            if table_kind == kind {
+                let start = offset as usize;
+                let table = data
+                    .get(start..start + size as usize)
+                    .ok_or_else(|| invalid("table extends past the end of the file"))?;
+                let mut table = Table {
+                    data: table,
+                    position: 0,
+                    format: 0,
+                };
+                // The format is always stored least significant byte first.
+                table.format = table.u32()?;
+                return Ok(Some(table));
+            }
+        }
+        Ok(None)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or_else(|| invalid("unexpected end of table"))?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn msb_first(&self) -> bool {
+        self.format & PCF_BYTE_MASK != 0
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes(2)?.try_into().unwrap();
+        Ok(if self.msb_first() {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.bytes(4)?.try_into().unwrap();
+        Ok(if self.msb_first() {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn i16(&mut self) -> Result<i32, Error> {
+        Ok(self.u16()? as i16 as i32)
+    }
+
+    fn i32(&mut self) -> Result<i32, Error> {
+        Ok(self.u32()? as i32)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidPcf(message.into())
+}
+
+fn required<'a>(data: &'a [u8], kind: u32, name: &str) -> Result<Table<'a>, Error> {
+    Table::find(data, kind)?.ok_or_else(|| Error::InvalidPcf(format!("missing {name} table")))
+}
+
+pub(crate) fn parse(data: &[u8]) -> Result<Font, Error> {
+    if !data.starts_with(PCF_MAGIC) {
+        return Err(invalid("missing PCF header"));
+    }
+
+    let metrics = parse_metrics(required(data, PCF_METRICS, "metrics")?)?;
+    let encodings = parse_encodings(required(data, PCF_BDF_ENCODINGS, "encodings")?)?;
+
+    let accelerators = match Table::find(data, PCF_BDF_ACCELERATORS)? {
+        Some(table) => Some(table),
+        None => Table::find(data, PCF_ACCELERATORS)?,
+    };
+    let (ascent, descent) = match accelerators {
+        Some(mut table) => {
+            table.bytes(8)?;
+            (table.i32()?, table.i32()?)
+        }
+        None => (
+            metrics.iter().map(|m| m.ascent).max().unwrap_or(0),
+            metrics.iter().map(|m| m.descent).max().unwrap_or(0),
+        ),
+    };
+    let (ascent, descent) = (ascent.max(0), descent.max(0));
+    let width = metrics.iter().map(|m| m.width).max().unwrap_or(0).max(0);
+    // Glyphs extending to the left of their origin shift the origin of all glyphs right.
+    let origin = -metrics
+        .iter()
+        .map(|m| m.left_bearing)
+        .min()
+        .unwrap_or(0)
+        .min(0);
+
+    let mut bitmaps = required(data, PCF_BITMAPS, "bitmaps")?;
+    let glyph_count = bitmaps.u32()? as usize;
+    if glyph_count != metrics.len() {
+        return Err(invalid("bitmap and metrics counts differ"));
+    }
+    let offsets = (0..glyph_count)
+        .map(|_| bitmaps.u32())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut sizes = [0; 4];
+    for size in &mut sizes {
+        *size = bitmaps.u32()? as usize;
+    }
+    let pad = 1 << (bitmaps.format & PCF_GLYPH_PAD_MASK);
+    let image = bitmaps.bytes(sizes[bitmaps.format as usize & PCF_GLYPH_PAD_MASK as usize])?;
+    let bit_msb_first = bitmaps.format & PCF_BIT_MASK != 0;
+    let scan_unit = 1 << ((bitmaps.format >> PCF_SCAN_UNIT_SHIFT) & 3);
+    // Bytes are swapped within scan units if the byte and bit orders differ.
+    let swap = bitmaps.msb_first() != bit_msb_first;
+
+    let mut font = Font::new(width as u32, (ascent + descent) as u32, ascent as u32);
+    for (c, index) in encodings.glyphs {
+        let (Some(glyph), Some(&offset)) = (metrics.get(index), offsets.get(index)) else {
+            return Err(invalid("encoding refers to a missing glyph"));
+        };
+        let glyph_width = glyph.right_bearing - glyph.left_bearing;
+        let row_bytes = ((glyph_width.max(0) as usize).div_ceil(8)).div_ceil(pad) * pad;
+
+        let mut bitmap = vec![false; (font.width * font.height) as usize];
+        for row in 0..(glyph.ascent + glyph.descent).max(0) {
+            for column in 0..glyph_width {
+                let mut byte = column as usize / 8;
+                if swap {
+                    byte = byte / scan_unit * scan_unit + (scan_unit - 1 - byte % scan_unit);
+                }
+                let Some(byte) = image.get(offset as usize + row as usize * row_bytes + byte)
+                else {
+                    return Err(invalid("glyph extends past the end of the bitmaps"));
+                };
+                let bit = if bit_msb_first {
+                    0x80 >> (column % 8)
+                } else {
+                    1 << (column % 8)
+                };
+                let x = origin + glyph.left_bearing + column;
+                let y = ascent - glyph.ascent + row;
+                if byte & bit != 0
+                    && (0..font.width as i32).contains(&x)
+                    && (0..font.height as i32).contains(&y)
+                {
+                    bitmap[(y as u32 * font.width + x as u32) as usize] = true;
+                }
+            }
+        }
+        font.glyphs.insert(c, bitmap);
+    }
+    font.default_char = encodings.default_char.filter(|c| font.contains(*c));
+    Ok(font)
+}
+
+fn parse_metrics(mut table: Table) -> Result<Vec<Metrics>, Error> {
+    if table.format & PCF_COMPRESSED_METRICS != 0 {
+        let count = table.u16()?;
+        (0..count)
+            .map(|_| {
+                let mut value = || Ok::<_, Error>(table.u8()? as i32 - 0x80);
+                Ok(Metrics {
+                    left_bearing: value()?,
+                    right_bearing: value()?,
+                    width: value()?,
+                    ascent: value()?,
+                    descent: value()?,
+                })
+            })
+            .collect()
+    } else {
+        let count = table.u32()?;
+        (0..count)
+            .map(|_| {
+                let metrics = Metrics {
+                    left_bearing: table.i16()?,
+                    right_bearing: table.i16()?,
+                    width: table.i16()?,
+                    ascent: table.i16()?,
+                    descent: table.i16()?,
+                };
+                table.u16()?; // attributes
+                Ok(metrics)
+            })
+            .collect()
+    }
+}
+
+/// Character encodings of the glyphs.
+struct Encodings {
+    default_char: Option<char>,
+    /// Glyph index of every encoded character.
+    glyphs: Vec<(char, usize)>,
+}
+
+fn parse_encodings(mut table: Table) -> Result<Encodings, Error> {
+    let (min_byte2, max_byte2) = (table.u16()? as u32, table.u16()? as u32);
+    let (min_byte1, max_byte1) = (table.u16()? as u32, table.u16()? as u32);
+    let default_char = char::from_u32(table.u16()? as u32);
+
+    let mut glyphs = Vec::new();
+    for byte1 in min_byte1..=max_byte1 {
+        for byte2 in min_byte2..=max_byte2 {
+            let index = table.u16()?;
+            if index == NO_GLYPH {
+                continue;
+            }
+            if let Some(c) = char::from_u32(byte1 << 8 | byte2) {
+                glyphs.push((c, index as usize));
+            }
+        }
+    }
+    Ok(Encodings {
+        default_char,
+        glyphs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Builds a PCF font of 4×6 characters with `glyphs` mapped to consecutive
+    /// code points from `'a'`, each given as rows of 4 bits.
+    fn pcf(glyphs: &[[u8; 6]], msb_first: bool) -> Vec<u8> {
+        let int32 = |value: u32| {
+            if msb_first {
+                value.to_be_bytes().to_vec()
+            } else {
+                value.to_le_bytes().to_vec()
+            }
+        };
+        let int16 = |value: u16| {
+            if msb_first {
+                value.to_be_bytes().to_vec()
+            } else {
+                value.to_le_bytes().to_vec()
+            }
+        };
+        // Glyph rows padded to 4 bytes, most significant byte and bit first,
+        // or least significant byte and bit first.
+        let format = if msb_first {
+            PCF_BYTE_MASK | PCF_BIT_MASK | 2
+        } else {
+            2
+        };
+
+        let mut metrics = (PCF_COMPRESSED_METRICS | format).to_le_bytes().to_vec();
+        metrics.extend(int16(glyphs.len() as u16));
+        for _ in glyphs {
+            metrics.extend([0x80, 0x84, 0x84, 0x85, 0x81]);
+        }
+
+        let mut bitmaps = format.to_le_bytes().to_vec();
+        bitmaps.extend(int32(glyphs.len() as u32));
+        for index in 0..glyphs.len() {
+            bitmaps.extend(int32(index as u32 * 24));
+        }
+        for pad in 0..4 {
+            bitmaps.extend(int32(if pad == 2 {
+                glyphs.len() as u32 * 24
+            } else {
+                0
+            }));
+        }
+        for glyph in glyphs {
+            for row in glyph {
+                let byte = if msb_first {
+                    row << 4
+                } else {
+                    row.reverse_bits() >> 4
+                };
+                bitmaps.extend([byte, 0, 0, 0]);
+            }
+        }
+
+        let mut encodings = format.to_le_bytes().to_vec();
+        for value in [
+            b'a' as u16,
+            b'a' as u16 + glyphs.len() as u16,
+            0,
+            0,
+            b'a' as u16,
+        ] {
+            encodings.extend(int16(value));
+        }
+        for index in 0..glyphs.len() {
+            encodings.extend(int16(index as u16));
+        }
+        encodings.extend(int16(NO_GLYPH));
+
+        let tables = [
+            (PCF_METRICS, metrics),
+            (PCF_BITMAPS, bitmaps),
+            (PCF_BDF_ENCODINGS, encodings),
+        ];
+        let mut data = PCF_MAGIC.to_vec();
+        data.extend((tables.len() as u32).to_le_bytes());
+        let mut offset = 8 + 16 * tables.len();
+        for (kind, table) in &tables {
+            for value in [*kind, 0, table.len() as u32, offset as u32] {
+                data.extend(value.to_le_bytes());
+            }
+            offset += table.len();
+        }
+        for (_, table) in tables {
+            data.extend(table);
+        }
+        data
+    }
+
+    const GLYPHS: [[u8; 6]; 2] = [
+        [0b0000, 0b0110, 0b0001, 0b0111, 0b1001, 0b0111],
+        [0b1000, 0b1000, 0b1110, 0b1001, 0b1001, 0b1110],
+    ];
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    fn parses_font(#[case] msb_first: bool) {
+        let font = parse(&pcf(&GLYPHS, msb_first)).unwrap();
+        assert_eq!(font.character_size(), (4, 6));
+        assert_eq!(font.ascent(), 5);
+        assert_eq!(font.default_char, Some('a'));
+        assert_eq!(font.chars().collect::<Vec<_>>(), ['a', 'b']);
+
+        for (c, glyph) in ['a', 'b'].into_iter().zip(GLYPHS) {
+            for (y, row) in glyph.into_iter().enumerate() {
+                for x in 0..4 {
+                    let expected = row & (0b1000 >> x) != 0;
+                    assert_eq!(font.pixel(c, x, y as u32), Some(expected), "{c} {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    fn reports_missing_tables() {
+        let mut data = PCF_MAGIC.to_vec();
+        data.extend(0u32.to_le_bytes());
+        assert!(matches!(parse(&data), Err(Error::InvalidPcf(_))));
+    }
+
+    #[rstest]
+    fn reports_truncated_font() {
+        let data = pcf(&GLYPHS, true);
+        assert!(matches!(
+            parse(&data[..data.len() - 8]),
+            Err(Error::InvalidPcf(_))
+        ));
+    }
+}
@@ -0,0 +1,390 @@
+//! Selection of the characters to keep in a font.
+
+use std::collections::BTreeSet;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use crate::Error;
+
+/// Characters used by Ratatui's built-in symbols and widgets.
+///
+/// Box-drawing, block-element, Braille and sextant characters are drawn procedurally
+/// by mousefood unless `EmbeddedBackendConfig::procedural_glyphs` is disabled,
+/// so fonts don't need them in that case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolSet {
+    /// Printable ASCII characters (U+0020–U+007E).
+    Ascii,
+    /// Box-drawing characters (U+2500–U+257F),
+    /// used by `symbols::line`, `symbols::border` and scrollbar tracks.
+    BoxDrawing,
+    /// Block elements (U+2580–U+259F), used by `symbols::bar`, `symbols::block`,
+    /// `symbols::half_block`, `symbols::shade`, quadrant borders and scrollbar thumbs.
+    BlockElements,
+    /// Braille patterns (U+2800–U+28FF), used by the Braille canvas marker.
+    Braille,
+    /// Sextants (U+1FB00–U+1FB3B).
+    Sextants,
+    /// Arrows at the ends of scrollbars (`▲▼◄►↑↓←→`).
+    ScrollbarArrows,
+    /// The dot marker of canvases and charts (`•`).
+    Dot,
+}
+
+impl SymbolSet {
+    /// All symbol sets.
+    pub const ALL: [SymbolSet; 7] = [
+        SymbolSet::Ascii,
+        SymbolSet::BoxDrawing,
+        SymbolSet::BlockElements,
+        SymbolSet::Braille,
+        SymbolSet::Sextants,
+        SymbolSet::ScrollbarArrows,
+        SymbolSet::Dot,
+    ];
+
+    /// Returns an iterator over the characters of the set.
+    pub fn chars(self) -> impl Iterator<Item = char> {
+        let (ranges, symbols): (&[(char, char)], &str) = match self {
+            SymbolSet::Ascii => (&[(' ', '~')], ""),
+            SymbolSet::BoxDrawing => (&[('\u{2500}', '\u{257F}')], ""),
+            SymbolSet::BlockElements => (&[('\u{2580}', '\u{259F}')], ""),
+            SymbolSet::Braille => (&[('\u{2800}', '\u{28FF}')], ""),
+            SymbolSet::Sextants => (&[('\u{1FB00}', '\u{1FB3B}')], ""),
+            SymbolSet::ScrollbarArrows => (&[], "▲▼◄►↑↓←→"),
+            SymbolSet::Dot => (&[], "•"),
+        };
+        ranges
+            .iter()
+            .flat_map(|&(start, end)| start..=end)
+            .chain(symbols.chars())
+    }
+}
+
+/// A set of characters to keep in a font, see [`Font::subset`](crate::Font::subset).
+///
+/// Characters can be collected from string literals in the application's sources,
+/// from lists of code points and from Ratatui's [symbol sets](SymbolSet).
+///
+/// ```no_run
+/// use mousefood_fontgen::{Font, Subset, SymbolSet};
+///
+/// let mut subset = Subset::new();
+/// subset.add_symbols(SymbolSet::Ascii);
+/// subset.add_sources("src")?;
+/// subset.add_code_points("U+00C0..U+00FF # Latin-1 letters")?;
+///
+/// let mut font = Font::open("fonts/terminus-8x16.bdf")?;
+/// font.subset(&subset);
+/// # Ok::<(), mousefood_fontgen::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Subset {
+    chars: BTreeSet<char>,
+}
+
+impl Subset {
+    /// Creates an empty subset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the subset contains `c`.
+    pub fn contains(&self, c: char) -> bool {
+        self.chars.contains(&c)
+    }
+
+    /// Returns an iterator over the characters of the subset, in ascending order.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars.iter().copied()
+    }
+
+    /// Adds `chars` to the subset.
+    pub fn add_chars(&mut self, chars: impl IntoIterator<Item = char>) {
+        self.chars.extend(chars);
+    }
+
+    /// Adds the characters of a Ratatui symbol set.
+    pub fn add_symbols(&mut self, set: SymbolSet) {
+        self.add_chars(set.chars());
+    }
+
+    /// Adds the code points of a list such as `U+0041, U+2500..U+257F, 0xE000-0xE0FF`.
+    ///
+    /// Code points are written in hexadecimal, prefixed with `U+` or `0x`,
+    /// and ranges are inclusive. Entries are separated by commas or whitespace,
+    /// and `#` starts a comment that extends to the end of the line.
+    pub fn add_code_points(&mut self, list: &str) -> Result<(), Error> {
+        let entries = list
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split([',', ' ', '\t']))
+            .filter(|entry| !entry.is_empty());
+        for entry in entries {
+            let invalid = || Error::InvalidCodePoint(entry.into());
+            let (start, end) = match entry.split_once("..").or_else(|| entry.split_once('-')) {
+                Some((start, end)) => (start, end.trim_start_matches('=')),
+                None => (entry, entry),
+            };
+            let (start, end) = (
+                code_point(start).ok_or_else(invalid)?,
+                code_point(end).ok_or_else(invalid)?,
+            );
+            if start > end {
+                return Err(invalid());
+            }
+            self.add_chars(start..=end);
+        }
+        Ok(())
+    }
+
+    /// Adds the characters of every string and character literal in the Rust `source`.
+    ///
+    /// Comments, byte strings and control characters are ignored.
+    pub fn add_string_literals(&mut self, source: &str) {
+        let mut literals = String::new();
+        Lexer {
+            chars: source.chars().peekable(),
+            literals: &mut literals,
+        }
+        .run();
+        self.add_chars(literals.chars().filter(|c| !c.is_control()));
+    }
+
+    /// Adds the characters of the string and character literals in all `.rs` files
+    /// in `path`, which may be a file or a directory searched recursively.
+    ///
+    /// In a build script, emit `cargo::rerun-if-changed` for `path`,
+    /// so that the font is regenerated when the sources change.
+    pub fn add_sources(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            for entry in std::fs::read_dir(path)? {
+                let path = entry?.path();
+                if path.is_dir() || path.extension().is_some_and(|extension| extension == "rs") {
+                    self.add_sources(path)?;
+                }
+            }
+        } else {
+            self.add_string_literals(&std::fs::read_to_string(path)?);
+        }
+        Ok(())
+    }
+}
+
+fn code_point(text: &str) -> Option<char> {
+    let hex = text
+        .strip_prefix("U+")
+        .or_else(|| text.strip_prefix("u+"))
+        .or_else(|| text.strip_prefix("0x"))?;
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+/// Collects the content of string and character literals of Rust source.
+struct Lexer<'a, 'b> {
+    chars: Peekable<Chars<'a>>,
+    literals: &'b mut String,
+}
+
+impl Lexer<'_, '_> {
+    fn run(&mut self) {
+        while let Some(c) = self.chars.next() {
+            match c {
+                '/' if self.chars.next_if_eq(&'/').is_some() => {
+                    self.chars.by_ref().find(|&c| c == '\n');
+                }
+                '/' if self.chars.next_if_eq(&'*').is_some() => self.block_comment(),
+                '"' => self.string(true),
+                '\'' => self.char_or_lifetime(true),
+                c if c.is_alphanumeric() || c == '_' => self.identifier(c),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips a block comment, which may contain nested ones.
+    fn block_comment(&mut self) {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.chars.next() {
+                Some('/') if self.chars.next_if_eq(&'*').is_some() => depth += 1,
+                Some('*') if self.chars.next_if_eq(&'/').is_some() => depth -= 1,
+                Some(_) => {}
+                None => return,
+            }
+        }
+    }
+
+    /// Skips an identifier or keyword starting with `first`,
+    /// and reads the literal it prefixes, e.g. the `r` of raw strings.
+    fn identifier(&mut self, first: char) {
+        let mut identifier = String::from(first);
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+            identifier.push(c);
+        }
+        let keep = !identifier.starts_with('b');
+        match (identifier.as_str(), self.chars.peek()) {
+            ("r" | "br" | "cr", Some('"' | '#')) => self.raw_string(keep),
+            ("b" | "c", Some('"')) => {
+                self.chars.next();
+                self.string(keep);
+            }
+            ("b", Some('\'')) => {
+                self.chars.next();
+                self.char_or_lifetime(false);
+            }
+            _ => {}
+        }
+    }
+
+    /// Reads a string after its opening quote.
+    fn string(&mut self, keep: bool) {
+        while let Some(c) = self.chars.next() {
+            let c = match c {
+                '"' => return,
+                '\\' => match self.escape() {
+                    Some(c) => c,
+                    None => continue,
+                },
+                c => c,
+            };
+            if keep {
+                self.literals.push(c);
+            }
+        }
+    }
+
+    /// Reads a raw string starting at the hashes before its opening quote.
+    fn raw_string(&mut self, keep: bool) {
+        let mut hashes = 0;
+        while self.chars.next_if_eq(&'#').is_some() {
+            hashes += 1;
+        }
+        if self.chars.next_if_eq(&'"').is_none() {
+            return;
+        }
+        let mut content = String::new();
+        while let Some(c) = self.chars.next() {
+            if c == '"' {
+                let mut closing = 0;
+                while closing < hashes && self.chars.next_if_eq(&'#').is_some() {
+                    closing += 1;
+                }
+                if closing == hashes {
+                    break;
+                }
+                content.push('"');
+                content.extend(std::iter::repeat_n('#', closing));
+            } else {
+                content.push(c);
+            }
+        }
+        if keep {
+            self.literals.push_str(&content);
+        }
+    }
+
+    /// Reads a character literal after its opening quote, or skips a lifetime.
+    fn char_or_lifetime(&mut self, keep: bool) {
+        let c = match self.chars.next() {
+            Some('\\') => self.escape(),
+            c => c,
+        };
+        if self.chars.next_if_eq(&'\'').is_some() && keep {
+            self.literals.extend(c);
+        }
+    }
+
+    /// Reads an escape sequence after its backslash.
+    ///
+    /// Returns `None` for line continuations, whose leading whitespace is skipped.
+    fn escape(&mut self) -> Option<char> {
+        match self.chars.next()? {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            '0' => Some('\0'),
+            'x' => {
+                let hex: String = self.chars.by_ref().take(2).collect();
+                u8::from_str_radix(&hex, 16).ok().map(char::from)
+            }
+            'u' => {
+                self.chars.next_if_eq(&'{')?;
+                let hex: String = self.chars.by_ref().take_while(|&c| c != '}').collect();
+                char::from_u32(u32::from_str_radix(&hex.replace('_', ""), 16).ok()?)
+            }
+            '\n' => {
+                while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+                None
+            }
+            c => Some(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn literals(source: &str) -> String {
+        let mut subset = Subset::new();
+        subset.add_string_literals(source);
+        subset.chars().collect()
+    }
+
+    #[rstest]
+    #[case(r#"let s = "héllo";"#, "hloé")]
+    #[case(r#"let c = 'ż'; let d = '\u{2713}';"#, "ż✓")]
+    #[case(r#"fn f<'a>(s: &'a str) -> &'static str { "ok" }"#, "ko")]
+    #[case(r##"let s = r#"say "hi""#;"##, " \"ahisy")]
+    #[case(r#"let s = "a\"b\\c\x41\n";"#, "\"A\\abc")]
+    #[case("let s = \"ab\\\n    cd\";", "abcd")]
+    #[case(r#"let b = b"xyz"; let c = b'q'; let r = br"uvw";"#, "")]
+    #[case(
+        r#"// "comment" '√'
+        /* "block /* nested */ comment" */ let s = "→";"#,
+        "→"
+    )]
+    #[case(r#"let number = 1_000u32; let raw_name = r#type;"#, "")]
+    fn collects_string_literals(#[case] source: &str, #[case] expected: &str) {
+        assert_eq!(literals(source), expected);
+    }
+
+    #[rstest]
+    #[case("U+0041", "A")]
+    #[case("U+0041, U+0043", "AC")]
+    #[case("0x61..0x63 # comment, U+0041\nU+2500", "abc─")]
+    #[case("U+0061-U+0063\tu+0031..=u+0032", "12abc")]
+    fn parses_code_points(#[case] list: &str, #[case] expected: &str) {
+        let mut subset = Subset::new();
+        subset.add_code_points(list).unwrap();
+        assert_eq!(subset.chars().collect::<String>(), expected);
+    }
+
+    #[rstest]
+    #[case("41")]
+    #[case("U+ZZ")]
+    #[case("U+0063..U+0061")]
+    #[case("U+D800")]
+    fn rejects_invalid_code_points(#[case] list: &str) {
+        let mut subset = Subset::new();
+        assert!(matches!(
+            subset.add_code_points(list),
+            Err(Error::InvalidCodePoint(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(SymbolSet::Ascii, 95)]
+    #[case(SymbolSet::BoxDrawing, 128)]
+    #[case(SymbolSet::BlockElements, 32)]
+    #[case(SymbolSet::Braille, 256)]
+    #[case(SymbolSet::Sextants, 60)]
+    #[case(SymbolSet::ScrollbarArrows, 8)]
+    #[case(SymbolSet::Dot, 1)]
+    fn symbol_sets_have_all_chars(#[case] set: SymbolSet, #[case] expected: usize) {
+        assert_eq!(set.chars().count(), expected);
+    }
+}
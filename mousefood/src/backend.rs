@@ -1,15 +1,17 @@
 use alloc::boxed::Box;
-use core::marker::PhantomData;
+use alloc::vec::Vec;
+use core::cell::RefCell;
 
+use crate::cache::GlyphCache;
 use crate::colors::*;
 use crate::default_font;
-use crate::framebuffer;
-use embedded_graphics::Drawable;
+use crate::error::DrawTargetError;
+use crate::font::Font;
+use crate::render::{Renderer, is_wide};
+use crate::target::{Output, Target};
 use embedded_graphics::draw_target::DrawTarget;
-use embedded_graphics::geometry::{self, Dimensions};
-use embedded_graphics::mono_font::{MonoFont, MonoTextStyleBuilder};
+use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::pixelcolor::{PixelColor, Rgb888};
-use embedded_graphics::text::Text;
 use ratatui_core::backend::{Backend, ClearType};
 use ratatui_core::layout;
 use ratatui_core::style;
@@ -25,6 +27,78 @@ pub enum TerminalAlignment {
     End,
 }
 
+/// Glyph drawn for characters missing from all configured fonts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplacementGlyph {
+    /// Outline of a box.
+    HollowBox,
+    /// Box containing the code point of the character in hexadecimal,
+    /// or just its outline if the digits don't fit into the cell.
+    HexBox,
+}
+
+/// Shape of the line drawn under underlined cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnderlineStyle {
+    /// Single straight line.
+    #[default]
+    Straight,
+    /// Two straight lines.
+    Double,
+    /// Wavy line, as used for spelling and diagnostic errors.
+    Curly,
+    /// Dotted line.
+    Dotted,
+    /// Dashed line.
+    Dashed,
+}
+
+/// Empty space, in pixels, between the edges of the display and the terminal grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Padding {
+    /// Padding on the left side.
+    pub left: u16,
+    /// Padding on the right side.
+    pub right: u16,
+    /// Padding on the top side.
+    pub top: u16,
+    /// Padding on the bottom side.
+    pub bottom: u16,
+}
+
+impl Padding {
+    /// No padding.
+    pub const ZERO: Padding = Padding::uniform(0);
+
+    /// Creates padding of the same size on every side.
+    pub const fn uniform(value: u16) -> Self {
+        Self {
+            left: value,
+            right: value,
+            top: value,
+            bottom: value,
+        }
+    }
+
+    /// Creates padding with separate horizontal (left and right)
+    /// and vertical (top and bottom) sizes.
+    pub const fn symmetric(horizontal: u16, vertical: u16) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+/// Callback fired after each buffer flush, e.g. to refresh an e-ink display.
+///
+/// It receives the display the frame was drawn to. Errors returned by the callback
+/// are returned from [`flush`](Backend::flush) as
+/// [`Error::FlushError`](crate::error::Error::FlushError).
+pub type FlushCallback<D> = Box<dyn FnMut(&mut D) -> Result<(), DrawTargetError>>;
+
 /// Embedded backend configuration.
 pub struct EmbeddedBackendConfig<D, C>
 where
@@ -32,13 +106,126 @@ where
     C: PixelColor,
 {
     /// Callback fired after each buffer flush.
-    pub flush_callback: Box<dyn FnMut(&mut D)>,
+    pub flush_callback: FlushCallback<D>,
     /// Regular font.
-    pub font_regular: MonoFont<'static>,
+    ///
+    /// Its character size determines the size of the terminal cells.
+    /// Glyphs of other fonts of a different size are centered in the cell and clipped to it.
+    pub font_regular: Font,
     /// Bold font.
-    pub font_bold: Option<MonoFont<'static>>,
+    pub font_bold: Option<Font>,
     /// Italic font.
-    pub font_italic: Option<MonoFont<'static>>,
+    pub font_italic: Option<Font>,
+    /// Bold italic font.
+    ///
+    /// If `None`, cells both bold and italic use the bold font,
+    /// then the italic font, then the regular font, whichever is configured first.
+    pub font_bold_italic: Option<Font>,
+    /// Font for double-width characters, such as CJK ideographs and emoji.
+    ///
+    /// Its glyphs should be twice as wide as those of the regular font.
+    /// Double-width characters take two cells, and are drawn from the fonts of their cell
+    /// centered across both cells if the double-width font is `None` or lacks them.
+    pub font_wide: Option<Font>,
+    /// Fonts used, in order, for characters missing from the font selected for a cell.
+    ///
+    /// This allows combining small per-script fonts, e.g. ASCII, Latin-1, symbols and CJK,
+    /// instead of a single large one. Like the bold and italic fonts, fallback fonts
+    /// of a different size than the regular font are centered in the cell and clipped to it.
+    pub font_fallbacks: Vec<Font>,
+    /// Glyph drawn for characters missing from the cell's font and all fallbacks.
+    ///
+    /// If `None`, the replacement glyph of the cell's font is used.
+    pub replacement_glyph: Option<ReplacementGlyph>,
+    /// Callback reporting characters missing from the cell's font and all fallbacks.
+    ///
+    /// It is called on flush, once for each missing character of the cells drawn since
+    /// the previous flush.
+    pub missing_glyph_callback: Option<Box<dyn FnMut(char)>>,
+
+    /// Additional horizontal space, in pixels, between adjacent cells.
+    ///
+    /// The space is filled with the cell background color.
+    pub letter_spacing: u16,
+
+    /// Additional vertical space, in pixels, between adjacent rows.
+    ///
+    /// The space is filled with the cell background color.
+    pub line_spacing: u16,
+
+    /// Space, in pixels, kept free around the terminal grid.
+    pub padding: Padding,
+
+    /// Integer factor by which glyphs are magnified.
+    ///
+    /// Each font pixel is drawn as a square of `scale` × `scale` display pixels.
+    /// If the magnified cells don't fit on the display, the terminal grid is empty.
+    pub scale: u32,
+
+    /// Draw box-drawing (U+2500–U+257F), block-element and quadrant (U+2580–U+259F),
+    /// Braille (U+2800–U+28FF) and sextant (U+1FB00–U+1FB3B) characters
+    /// procedurally instead of using glyphs from the fonts.
+    ///
+    /// Procedural glyphs fill the whole cell, so lines join seamlessly
+    /// across cells, and are available even with fonts that lack them.
+    /// Braille dots, quadrants and sextants are drawn as exact rectangles
+    /// covering the cell, giving
+    /// [`Canvas`](https://docs.rs/ratatui/latest/ratatui/widgets/canvas/struct.Canvas.html)
+    /// and charts the full resolution of the display.
+    pub procedural_glyphs: bool,
+
+    /// Embolden glyphs of bold cells when no bold font is configured.
+    ///
+    /// The glyph is drawn a second time, shifted one pixel to the right
+    /// and clipped to the cell, which thickens its vertical strokes.
+    pub synthetic_bold: bool,
+
+    /// Brighten the foreground color of cells emboldened by
+    /// [`synthetic_bold`](Self::synthetic_bold).
+    ///
+    /// Named colors are replaced by their light variants and RGB colors are lightened.
+    /// This has no effect on binary color displays.
+    pub brighten_bold: bool,
+
+    /// Slant glyphs of italic cells when no italic font is configured.
+    ///
+    /// Glyph rows are shifted progressively to the right above the baseline,
+    /// and to the left below it, clipped to the cell.
+    pub synthetic_italic: bool,
+
+    /// Shape of the line drawn under cells with the
+    /// [`UNDERLINED`](style::Modifier::UNDERLINED) modifier.
+    ///
+    /// Ratatui has no per-cell underline style, so the style applies to all underlined cells.
+    /// The line is drawn in the cell's underline color, or its foreground color
+    /// if the underline color is [`Color::Reset`](style::Color::Reset).
+    pub underline_style: UnderlineStyle,
+
+    /// Thickness of the underline in font pixels.
+    ///
+    /// If `None`, the thickness given by the font is used.
+    pub underline_thickness: Option<u32>,
+
+    /// Distance from the top of the glyphs to the top of the underline, in font pixels.
+    ///
+    /// If `None`, the position given by the font is used.
+    pub underline_offset: Option<u32>,
+
+    /// Memory, in bytes, available for caching rasterised glyphs.
+    ///
+    /// Cells with the same symbol and bold and italic modifiers reuse the glyph
+    /// rasterised for the first of them, and the least recently used glyphs are dropped
+    /// when the cache is full. A glyph takes about one byte per pixel of the cell, e.g.
+    /// 60 bytes for a 6x10 font, plus some bookkeeping. `0` disables the cache.
+    pub glyph_cache_size: usize,
+
+    /// Color of the display area outside the terminal grid,
+    /// i.e. the padding and pixels left over by the alignment.
+    ///
+    /// It is painted on every flush, so the margins can serve as a border
+    /// around the terminal. [`Color::Reset`](style::Color::Reset) uses the
+    /// default background color.
+    pub margin_color: style::Color,
 
     /// Determines how the view is vertically aligned when the display height
     /// is not an exact multiple of the font height.
@@ -56,10 +243,66 @@ where
 {
     fn default() -> Self {
         Self {
-            flush_callback: Box::new(|_| {}),
-            font_regular: default_font::regular,
+            flush_callback: Box::new(|_| Ok(())),
+            font_regular: default_font::regular.into(),
             font_bold: None,
             font_italic: None,
+            font_bold_italic: None,
+            font_wide: None,
+            font_fallbacks: Vec::new(),
+            replacement_glyph: None,
+            missing_glyph_callback: None,
+            letter_spacing: 0,
+            line_spacing: 0,
+            padding: Padding::ZERO,
+            scale: 1,
+            procedural_glyphs: true,
+            synthetic_bold: false,
+            brighten_bold: false,
+            synthetic_italic: false,
+            underline_style: UnderlineStyle::Straight,
+            underline_thickness: None,
+            underline_offset: None,
+            glyph_cache_size: 0,
+            margin_color: style::Color::Reset,
+            vertical_alignment: TerminalAlignment::Start,
+            horizontal_alignment: TerminalAlignment::Start,
+        }
+    }
+}
+
+/// Mirror display configuration.
+///
+/// See [`EmbeddedBackend::add_mirror`].
+pub struct MirrorConfig<D, C>
+where
+    D: DrawTarget<Color = C>,
+    C: PixelColor,
+{
+    /// Callback fired after each buffer flush.
+    pub flush_callback: FlushCallback<D>,
+
+    /// Space, in pixels, kept free around the terminal grid.
+    pub padding: Padding,
+
+    /// Determines how the view is vertically aligned when the display height
+    /// is not an exact multiple of the font height.
+    pub vertical_alignment: TerminalAlignment,
+
+    /// Determines how the view is horizontally aligned when the display width
+    /// is not an exact multiple of the font width.
+    pub horizontal_alignment: TerminalAlignment,
+}
+
+impl<D, C> Default for MirrorConfig<D, C>
+where
+    D: DrawTarget<Color = C>,
+    C: PixelColor,
+{
+    fn default() -> Self {
+        Self {
+            flush_callback: Box::new(|_| Ok(())),
+            padding: Padding::ZERO,
             vertical_alignment: TerminalAlignment::Start,
             horizontal_alignment: TerminalAlignment::Start,
         }
@@ -81,89 +324,257 @@ where
     D: DrawTarget<Color = C> + 'display,
     C: PixelColor + 'display,
 {
-    display: &'display mut D,
-    display_type: PhantomData<D>,
-
-    flush_callback: Box<dyn FnMut(&mut D)>,
+    display: Target<'display, D, C>,
+    mirrors: Vec<Box<dyn Output + 'display>>,
 
-    buffer: framebuffer::HeapBuffer<C>,
+    renderer: Renderer,
 
-    font_regular: MonoFont<'static>,
-    font_bold: Option<MonoFont<'static>>,
-    font_italic: Option<MonoFont<'static>>,
-
-    char_offset: geometry::Point,
+    missing_glyph_callback: Option<Box<dyn FnMut(char)>>,
+    missing_glyphs: Vec<char>,
 
     columns_rows: layout::Size,
     pixels: layout::Size,
+    cursor: layout::Position,
 }
 
 impl<'display, D, C> EmbeddedBackend<'display, D, C>
 where
     D: DrawTarget<Color = C> + Dimensions + 'static,
+    D::Error: Send + Sync,
     C: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor> + 'static,
 {
-    fn init(
+    /// Creates a new `EmbeddedBackend` using default fonts.
+    pub fn new(
         display: &'display mut D,
-        flush_callback: impl FnMut(&mut D) + 'static,
-        font_regular: MonoFont<'static>,
-        font_bold: Option<MonoFont<'static>>,
-        font_italic: Option<MonoFont<'static>>,
-        vertical_alignment: TerminalAlignment,
-        horizontal_alignment: TerminalAlignment,
+        config: EmbeddedBackendConfig<D, C>,
     ) -> EmbeddedBackend<'display, D, C> {
-        let pixels = layout::Size {
-            width: display.bounding_box().size.width as u16,
-            height: display.bounding_box().size.height as u16,
-        };
-
-        let extra_x = pixels.width % font_regular.character_size.width as u16;
-        let extra_y = pixels.height % font_regular.character_size.height as u16;
-
-        let off_x = match horizontal_alignment {
-            TerminalAlignment::Start => 0,
-            TerminalAlignment::Center => extra_x / 2, //best effort, might be 1/2 pixel off
-            TerminalAlignment::End => extra_x,
-        } as i32;
-        let off_y = match vertical_alignment {
-            TerminalAlignment::Start => 0,
-            TerminalAlignment::Center => extra_y / 2, //best effort, might be 1/2 pixel off
-            TerminalAlignment::End => extra_y,
-        } as i32;
-
-        let char_offset = geometry::Point::new(off_x, off_y);
+        let display = Target::new(
+            display,
+            config.flush_callback,
+            config.padding,
+            config.vertical_alignment,
+            config.horizontal_alignment,
+        );
 
-        Self {
-            buffer: framebuffer::HeapBuffer::new(display.bounding_box()),
+        let mut backend = Self {
             display,
-            display_type: PhantomData,
-            flush_callback: Box::new(flush_callback),
-            font_regular,
-            font_bold,
-            font_italic,
-            char_offset,
-            columns_rows: layout::Size {
-                height: pixels.height / font_regular.character_size.height as u16,
-                width: pixels.width / font_regular.character_size.width as u16,
+            mirrors: Vec::new(),
+            renderer: Renderer {
+                font_regular: config.font_regular,
+                font_bold: config.font_bold,
+                font_italic: config.font_italic,
+                font_bold_italic: config.font_bold_italic,
+                font_wide: config.font_wide,
+                font_fallbacks: config.font_fallbacks,
+                letter_spacing: config.letter_spacing,
+                line_spacing: config.line_spacing,
+                margin_color: config.margin_color,
+                scale: config.scale.max(1),
+                procedural_glyphs: config.procedural_glyphs,
+                synthetic_bold: config.synthetic_bold,
+                brighten_bold: config.brighten_bold,
+                synthetic_italic: config.synthetic_italic,
+                replacement_glyph: config.replacement_glyph,
+                underline_style: config.underline_style,
+                underline_thickness: config.underline_thickness,
+                underline_offset: config.underline_offset,
+                glyph_cache: RefCell::new(GlyphCache::new(config.glyph_cache_size)),
             },
-            pixels,
-        }
+            missing_glyph_callback: config.missing_glyph_callback,
+            missing_glyphs: Vec::new(),
+            columns_rows: layout::Size::default(),
+            pixels: layout::Size::default(),
+            cursor: layout::Position::ORIGIN,
+        };
+        // Cells that don't fit on the display leave the grid empty.
+        let _ = backend.update_grid();
+        backend
     }
 
-    /// Creates a new `EmbeddedBackend` using default fonts.
-    pub fn new(
-        display: &'display mut D,
-        config: EmbeddedBackendConfig<D, C>,
-    ) -> EmbeddedBackend<'display, D, C> {
-        Self::init(
+    /// Adds a display that mirrors the terminal.
+    ///
+    /// The mirror may use a different color type and size than the main display.
+    /// Every cell is drawn to each display with its own color conversion, and
+    /// [`flush`](Backend::flush) sends the frame to all of them.
+    ///
+    /// The terminal grid is shrunk to fit the smallest display, and is placed on
+    /// each display according to its own alignment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use mousefood::prelude::*;
+    ///
+    /// let mut backend = EmbeddedBackend::new(&mut tft, EmbeddedBackendConfig::default());
+    /// backend.add_mirror(&mut oled, MirrorConfig::default());
+    /// let mut terminal = Terminal::new(backend).unwrap();
+    /// ```
+    pub fn add_mirror<M, MC>(&mut self, display: &'display mut M, config: MirrorConfig<M, MC>)
+    where
+        M: DrawTarget<Color = MC> + 'static,
+        M::Error: Send + Sync,
+        MC: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor> + 'static,
+    {
+        self.mirrors.push(Box::new(Target::new(
             display,
             config.flush_callback,
-            config.font_regular,
-            config.font_bold,
-            config.font_italic,
+            config.padding,
             config.vertical_alignment,
             config.horizontal_alignment,
-        )
+        )));
+        // Cells that don't fit on the mirror leave the grid empty.
+        let _ = self.update_grid();
+    }
+
+    /// Replaces the fonts.
+    ///
+    /// The terminal grid is recomputed to fit the new character size and the
+    /// displays are cleared. Ratatui picks up the new [`size`](Backend::size)
+    /// on the next draw, and redraws the whole frame. If the size didn't change,
+    /// call [`Terminal::clear`](ratatui_core::terminal::Terminal::clear) to redraw it.
+    ///
+    /// If the cells of the new fonts don't fit on the displays, the fonts are left unchanged
+    /// and [`Error::InvalidCellSize`](crate::error::Error::InvalidCellSize) is returned.
+    pub fn set_fonts(
+        &mut self,
+        font_regular: Font,
+        font_bold: Option<Font>,
+        font_italic: Option<Font>,
+        font_bold_italic: Option<Font>,
+    ) -> Result<()> {
+        let previous = (
+            core::mem::replace(&mut self.renderer.font_regular, font_regular),
+            core::mem::replace(&mut self.renderer.font_bold, font_bold),
+            core::mem::replace(&mut self.renderer.font_italic, font_italic),
+            core::mem::replace(&mut self.renderer.font_bold_italic, font_bold_italic),
+        );
+        if let Err(error) = self.update_grid() {
+            (
+                self.renderer.font_regular,
+                self.renderer.font_bold,
+                self.renderer.font_italic,
+                self.renderer.font_bold_italic,
+            ) = previous;
+            self.update_grid()?;
+            return Err(error);
+        }
+        self.renderer.glyph_cache.get_mut().clear();
+        self.clear()
+    }
+
+    /// Replaces the font for double-width characters.
+    ///
+    /// Cells drawn earlier are not redrawn, call
+    /// [`Terminal::clear`](ratatui_core::terminal::Terminal::clear) to redraw the whole frame.
+    /// See [`EmbeddedBackendConfig::font_wide`].
+    pub fn set_font_wide(&mut self, font_wide: Option<Font>) {
+        self.renderer.font_wide = font_wide;
+        self.renderer.glyph_cache.get_mut().clear();
+    }
+
+    /// Replaces the fallback fonts.
+    ///
+    /// Cells drawn earlier are not redrawn, call
+    /// [`Terminal::clear`](ratatui_core::terminal::Terminal::clear) to redraw the whole frame.
+    /// See [`EmbeddedBackendConfig::font_fallbacks`].
+    pub fn set_font_fallbacks(&mut self, font_fallbacks: Vec<Font>) {
+        self.renderer.font_fallbacks = font_fallbacks;
+        self.renderer.glyph_cache.get_mut().clear();
+    }
+
+    /// Changes the factor by which glyphs are magnified.
+    ///
+    /// If the magnified cells don't fit on the displays, the scale is left unchanged
+    /// and [`Error::InvalidCellSize`](crate::error::Error::InvalidCellSize) is returned.
+    ///
+    /// See [`EmbeddedBackendConfig::scale`] and [`set_fonts`](Self::set_fonts).
+    pub fn set_scale(&mut self, scale: u32) -> Result<()> {
+        let previous = core::mem::replace(&mut self.renderer.scale, scale.max(1));
+        if let Err(error) = self.update_grid() {
+            self.renderer.scale = previous;
+            self.update_grid()?;
+            return Err(error);
+        }
+        self.renderer.glyph_cache.get_mut().clear();
+        self.clear()
+    }
+
+    /// Changes the alignment of the terminal grid on the main display.
+    ///
+    /// See [`set_fonts`](Self::set_fonts).
+    pub fn set_alignment(
+        &mut self,
+        vertical_alignment: TerminalAlignment,
+        horizontal_alignment: TerminalAlignment,
+    ) -> Result<()> {
+        self.display
+            .set_alignment(vertical_alignment, horizontal_alignment);
+        self.relayout()
+    }
+
+    /// Recomputes the grid and clears the now stale framebuffers.
+    fn relayout(&mut self) -> Result<()> {
+        self.update_grid()?;
+        self.clear()
+    }
+
+    /// Computes the largest grid that fits on every display and places it on each of them.
+    ///
+    /// If the cells are empty or don't fit on a display, the grid is left empty
+    /// and an error is returned.
+    fn update_grid(&mut self) -> Result<()> {
+        let cell_size = self.renderer.cell_size().unwrap_or_default();
+        let cell = u16::try_from(cell_size.width)
+            .ok()
+            .zip(u16::try_from(cell_size.height).ok())
+            .filter(|&(width, height)| width > 0 && height > 0);
+
+        let mut columns_rows = layout::Size {
+            width: u16::MAX,
+            height: u16::MAX,
+        };
+        for output in self.outputs() {
+            let area = output.area();
+            columns_rows = match cell {
+                Some((width, height)) => layout::Size {
+                    width: columns_rows.width.min(area.width / width),
+                    height: columns_rows.height.min(area.height / height),
+                },
+                None => layout::Size::ZERO,
+            };
+        }
+        let fits = columns_rows.width > 0 && columns_rows.height > 0;
+        if !fits {
+            columns_rows = layout::Size::ZERO;
+        }
+
+        for output in self.outputs() {
+            output.set_grid(columns_rows, cell_size);
+        }
+        self.columns_rows = columns_rows;
+        // The grid fits on the displays, so its size in pixels fits in `u16`.
+        self.pixels = layout::Size {
+            width: (columns_rows.width as u32 * cell_size.width) as u16,
+            height: (columns_rows.height as u32 * cell_size.height) as u16,
+        };
+        if fits {
+            Ok(())
+        } else {
+            Err(crate::error::Error::InvalidCellSize)
+        }
+    }
+}
+
+impl<'display, D, C> EmbeddedBackend<'display, D, C>
+where
+    D: DrawTarget<Color = C> + 'static,
+    D::Error: Send + Sync,
+    C: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor> + 'static,
+{
+    /// Returns the main display followed by all mirrors.
+    fn outputs(&mut self) -> impl Iterator<Item = &mut (dyn Output + 'display)> {
+        core::iter::once(&mut self.display as &mut (dyn Output + 'display))
+            .chain(self.mirrors.iter_mut().map(|mirror| mirror.as_mut()))
     }
 }
 
@@ -172,6 +583,7 @@ type Result<T, E = crate::error::Error> = core::result::Result<T, E>;
 impl<D, C> Backend for EmbeddedBackend<'_, D, C>
 where
     D: DrawTarget<Color = C> + 'static,
+    D::Error: Send + Sync,
     C: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor> + 'static,
 {
     type Error = crate::error::Error;
@@ -180,52 +592,26 @@ where
     where
         I: Iterator<Item = (u16, u16, &'a ratatui_core::buffer::Cell)>,
     {
+        // The cell to the right of a double-width character is covered by it.
+        let mut continuation = None;
         for (x, y, cell) in content {
-            let position = geometry::Point::new(
-                x as i32 * self.font_regular.character_size.width as i32,
-                y as i32 * self.font_regular.character_size.height as i32,
-            );
-
-            let mut style_builder = MonoTextStyleBuilder::new()
-                .font(&self.font_regular)
-                .text_color(TermColor(cell.fg, TermColorType::Foreground).into())
-                .background_color(TermColor(cell.bg, TermColorType::Background).into());
-
-            for modifier in cell.modifier.iter() {
-                style_builder = match modifier {
-                    style::Modifier::BOLD => match &self.font_bold {
-                        None => style_builder.font(&self.font_regular),
-                        Some(font) => style_builder.font(font),
-                    },
-                    style::Modifier::DIM => style_builder, // TODO
-                    style::Modifier::ITALIC => match &self.font_italic {
-                        None => style_builder.font(&self.font_regular),
-                        Some(font) => style_builder.font(font),
-                    },
-                    style::Modifier::UNDERLINED => style_builder.underline(),
-                    style::Modifier::SLOW_BLINK => style_builder, // TODO
-                    style::Modifier::RAPID_BLINK => style_builder, // TODO
-                    style::Modifier::REVERSED => style_builder,   // TODO
-                    style::Modifier::HIDDEN => style_builder,     // TODO
-                    style::Modifier::CROSSED_OUT => style_builder.strikethrough(),
-                    _ => style_builder,
+            if continuation.take() == Some((x, y)) {
+                continue;
+            }
+            if is_wide(cell) {
+                continuation = Some((x + 1, y));
+            }
+            if self.missing_glyph_callback.is_some() {
+                if let Some(c) = self.renderer.missing_glyph(cell) {
+                    if let Err(index) = self.missing_glyphs.binary_search(&c) {
+                        self.missing_glyphs.insert(index, c);
+                    }
                 }
             }
-
-            if cell.underline_color != style::Color::Reset {
-                style_builder = style_builder.underline_with_color(
-                    TermColor(cell.underline_color, TermColorType::Foreground).into(),
-                );
+            self.display.draw_cell(&self.renderer, x, y, cell)?;
+            for mirror in &mut self.mirrors {
+                mirror.draw_cell(&self.renderer, x, y, cell)?;
             }
-
-            Text::with_baseline(
-                cell.symbol(),
-                position + self.char_offset,
-                style_builder.build(),
-                embedded_graphics::text::Baseline::Top,
-            )
-            .draw(&mut self.buffer)
-            .map_err(|_| crate::error::Error::DrawError)?;
         }
         Ok(())
     }
@@ -241,34 +627,45 @@ where
     }
 
     fn get_cursor_position(&mut self) -> Result<layout::Position> {
-        // TODO
-        Ok(layout::Position::new(0, 0))
+        Ok(self.cursor)
     }
 
-    fn set_cursor_position<P: Into<layout::Position>>(
-        &mut self,
-        #[allow(unused_variables)] position: P,
-    ) -> Result<()> {
-        // TODO
+    fn set_cursor_position<P: Into<layout::Position>>(&mut self, position: P) -> Result<()> {
+        // The cursor isn't drawn yet, only tracked for clear_region.
+        self.cursor = position.into();
         Ok(())
     }
 
     fn clear(&mut self) -> Result<()> {
-        self.buffer
-            .clear(TermColor(style::Color::Reset, TermColorType::Background).into())
-            .map_err(|_| crate::error::Error::DrawError)
+        self.outputs().try_for_each(|output| output.clear())
     }
 
     fn clear_region(&mut self, clear_type: ClearType) -> Result<()> {
-        match clear_type {
-            ClearType::All => self.clear(),
-            ClearType::AfterCursor
-            | ClearType::BeforeCursor
-            | ClearType::CurrentLine
-            | ClearType::UntilNewLine => Err(crate::error::Error::ClearTypeUnsupported(
-                alloc::format!("{:?}", clear_type),
-            )),
+        let grid = layout::Rect::from((layout::Position::ORIGIN, self.columns_rows));
+        let layout::Position { x, y } = self.cursor;
+        let (first, second) = match clear_type {
+            ClearType::All => return self.clear(),
+            ClearType::AfterCursor => (
+                layout::Rect::new(x.saturating_add(1), y, u16::MAX, 1),
+                layout::Rect::new(0, y.saturating_add(1), u16::MAX, u16::MAX),
+            ),
+            ClearType::BeforeCursor => (
+                layout::Rect::new(0, 0, u16::MAX, y),
+                layout::Rect::new(0, y, x, 1),
+            ),
+            ClearType::CurrentLine => (layout::Rect::new(0, y, u16::MAX, 1), layout::Rect::ZERO),
+            ClearType::UntilNewLine => (layout::Rect::new(x, y, u16::MAX, 1), layout::Rect::ZERO),
+        };
+
+        for area in [first, second] {
+            let area = area.intersection(grid);
+            if area.is_empty() {
+                continue;
+            }
+            self.outputs()
+                .try_for_each(|output| output.clear_cells(area))?;
         }
+        Ok(())
     }
 
     fn size(&self) -> Result<layout::Size> {
@@ -283,10 +680,474 @@ where
     }
 
     fn flush(&mut self) -> Result<()> {
-        self.display
-            .fill_contiguous(&self.display.bounding_box(), &self.buffer)
-            .map_err(|_| crate::error::Error::DrawError)?;
-        (self.flush_callback)(self.display);
+        if let Some(callback) = &mut self.missing_glyph_callback {
+            self.missing_glyphs.drain(..).for_each(callback);
+        }
+        self.display.flush(&self.renderer)?;
+        for mirror in &mut self.mirrors {
+            mirror.flush(&self.renderer)?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::{fixture, rstest};
+
+    use crate::framebuffer::HeapBuffer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::{BinaryColor, RgbColor};
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::Rectangle;
+    use ratatui_core::buffer::Cell;
+    use ratatui_core::style::Color;
+
+    #[fixture]
+    fn display() -> MockDisplay<Rgb888> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        display
+    }
+
+    #[fixture]
+    fn mirror() -> HeapBuffer<BinaryColor> {
+        HeapBuffer::new(Rectangle::new(Point::zero(), Size::new(34, 20)))
+    }
+
+    fn white_cell() -> Cell {
+        let mut cell = Cell::new(" ");
+        cell.set_bg(Color::White);
+        cell
+    }
+
+    #[rstest]
+    fn mirror_shrinks_grid(mut display: MockDisplay<Rgb888>, mut mirror: HeapBuffer<BinaryColor>) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        assert_eq!(backend.size().unwrap(), layout::Size::new(10, 6));
+
+        backend.add_mirror(&mut mirror, MirrorConfig::default());
+        assert_eq!(backend.size().unwrap(), layout::Size::new(5, 2));
+    }
+
+    #[rstest]
+    fn window_size_reports_grid_pixels(
+        mut display: MockDisplay<Rgb888>,
+        mut mirror: HeapBuffer<BinaryColor>,
+    ) {
+        let config = EmbeddedBackendConfig {
+            letter_spacing: 1,
+            padding: Padding::uniform(1),
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+        let window_size = backend.window_size().unwrap();
+        assert_eq!(window_size.columns_rows, layout::Size::new(8, 6));
+        assert_eq!(window_size.pixels, layout::Size::new(56, 60));
+
+        backend.add_mirror(&mut mirror, MirrorConfig::default());
+        let window_size = backend.window_size().unwrap();
+        assert_eq!(window_size.columns_rows, layout::Size::new(4, 2));
+        assert_eq!(window_size.pixels, layout::Size::new(28, 20));
+    }
+
+    #[rstest]
+    fn mirror_receives_frame(
+        mut display: MockDisplay<Rgb888>,
+        mut mirror: HeapBuffer<BinaryColor>,
+    ) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        backend.add_mirror(
+            &mut mirror,
+            MirrorConfig {
+                horizontal_alignment: TerminalAlignment::Center,
+                ..Default::default()
+            },
+        );
+
+        let cell = white_cell();
+        backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(6, 0)), Some(Rgb888::BLACK));
+
+        // 5 columns of 6 pixels leave 4 pixels, split evenly between both sides.
+        let mirror: Vec<BinaryColor> = mirror.into_iter().collect();
+        assert_eq!(mirror[1], BinaryColor::Off);
+        assert_eq!(mirror[2], BinaryColor::On);
+        assert_eq!(mirror[7], BinaryColor::On);
+        assert_eq!(mirror[8], BinaryColor::Off);
+    }
+
+    #[rstest]
+    #[case(0, 0, Padding::ZERO, layout::Size::new(10, 6))]
+    #[case(2, 0, Padding::ZERO, layout::Size::new(8, 6))]
+    #[case(0, 2, Padding::ZERO, layout::Size::new(10, 5))]
+    #[case(2, 2, Padding::uniform(2), layout::Size::new(7, 5))]
+    #[case(0, 0, Padding::symmetric(5, 0), layout::Size::new(9, 6))]
+    #[case(0, 0, Padding::uniform(40000), layout::Size::new(0, 0))]
+    fn spacing_and_padding_shrink_grid(
+        mut display: MockDisplay<Rgb888>,
+        #[case] letter_spacing: u16,
+        #[case] line_spacing: u16,
+        #[case] padding: Padding,
+        #[case] expected: layout::Size,
+    ) {
+        let config = EmbeddedBackendConfig {
+            letter_spacing,
+            line_spacing,
+            padding,
+            ..Default::default()
+        };
+        let backend = EmbeddedBackend::new(&mut display, config);
+        assert_eq!(backend.size().unwrap(), expected);
+    }
+
+    #[rstest]
+    fn padding_larger_than_display_is_margin(mut display: MockDisplay<Rgb888>) {
+        let config = EmbeddedBackendConfig {
+            padding: Padding::uniform(100),
+            margin_color: Color::White,
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+        assert_eq!(backend.size().unwrap(), layout::Size::new(0, 0));
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(63, 63)), Some(Rgb888::WHITE));
+    }
+
+    #[rstest]
+    fn spacing_is_filled_with_background(mut display: MockDisplay<Rgb888>) {
+        let config = EmbeddedBackendConfig {
+            letter_spacing: 2,
+            line_spacing: 2,
+            padding: Padding::uniform(1),
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+
+        let cell = white_cell();
+        backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(1, 1)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(8, 12)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(9, 12)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(8, 13)), Some(Rgb888::BLACK));
+    }
+
+    #[rstest]
+    fn margins_are_painted_on_flush(mut display: MockDisplay<Rgb888>) {
+        let config = EmbeddedBackendConfig {
+            padding: Padding::uniform(1),
+            margin_color: Color::Red,
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+
+        let cell = white_cell();
+        backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+        backend.clear().unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Rgb888::RED));
+        assert_eq!(display.get_pixel(Point::new(1, 1)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(60, 60)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(61, 30)), Some(Rgb888::RED));
+        assert_eq!(display.get_pixel(Point::new(30, 61)), Some(Rgb888::RED));
+        assert_eq!(display.get_pixel(Point::new(63, 63)), Some(Rgb888::RED));
+    }
+
+    #[rstest]
+    fn set_fonts_recomputes_grid(mut display: MockDisplay<Rgb888>) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        backend
+            .set_fonts(
+                embedded_graphics::mono_font::ascii::FONT_10X20.into(),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(backend.size().unwrap(), layout::Size::new(6, 3));
+    }
+
+    #[rstest]
+    fn scale_magnifies_cells(mut display: MockDisplay<Rgb888>) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        backend.set_scale(2).unwrap();
+        assert_eq!(backend.size().unwrap(), layout::Size::new(5, 3));
+
+        let cell = white_cell();
+        backend.draw([(1, 1, &cell)].into_iter()).unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(11, 20)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(12, 20)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(23, 39)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(24, 39)), Some(Rgb888::BLACK));
+        assert_eq!(display.get_pixel(Point::new(23, 40)), Some(Rgb888::BLACK));
+    }
+
+    #[rstest]
+    #[case::overflowing(1 << 30)]
+    #[case::truncated(10923)]
+    #[case::larger_than_display(11)]
+    fn invalid_scale_is_rejected(mut display: MockDisplay<Rgb888>, #[case] scale: u32) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        assert!(matches!(
+            backend.set_scale(scale),
+            Err(crate::error::Error::InvalidCellSize)
+        ));
+        assert_eq!(backend.size().unwrap(), layout::Size::new(10, 6));
+        assert_eq!(backend.renderer.scale, 1);
+    }
+
+    #[rstest]
+    fn empty_font_is_rejected(mut display: MockDisplay<Rgb888>) {
+        use embedded_graphics::mono_font::{MonoFont, ascii};
+
+        const EMPTY: MonoFont = MonoFont {
+            character_size: Size::zero(),
+            ..ascii::FONT_6X10
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        assert!(matches!(
+            backend.set_fonts(Font::new(EMPTY), None, None, None),
+            Err(crate::error::Error::InvalidCellSize)
+        ));
+        assert_eq!(backend.size().unwrap(), layout::Size::new(10, 6));
+    }
+
+    #[rstest]
+    fn oversized_scale_leaves_grid_empty(mut display: MockDisplay<Rgb888>) {
+        let config = EmbeddedBackendConfig {
+            scale: u32::MAX,
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+        assert_eq!(backend.size().unwrap(), layout::Size::ZERO);
+        backend.flush().unwrap();
+    }
+
+    #[rstest]
+    fn fallback_font_is_used_for_missing_glyphs(
+        mut display: MockDisplay<Rgb888>,
+        #[from(display)] mut expected: MockDisplay<Rgb888>,
+    ) {
+        use embedded_graphics::mono_font::{MonoTextStyle, ascii, iso_8859_1};
+        use embedded_graphics::text::{Baseline, Text};
+
+        let config = EmbeddedBackendConfig {
+            font_regular: ascii::FONT_6X10.into(),
+            font_fallbacks: alloc::vec![iso_8859_1::FONT_6X10.into()],
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+        let cell = Cell::new("é");
+        backend.draw([(0, 0, &cell)].into_iter()).unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        expected.clear(Rgb888::BLACK).unwrap();
+        let style = MonoTextStyle::new(&iso_8859_1::FONT_6X10, Rgb888::WHITE);
+        Text::with_baseline("é", Point::zero(), style, Baseline::Top)
+            .draw(&mut expected)
+            .unwrap();
+        display.assert_eq(&expected);
+    }
+
+    #[rstest]
+    fn reports_missing_glyphs_once(mut display: MockDisplay<Rgb888>) {
+        let missing = alloc::rc::Rc::new(core::cell::RefCell::new(Vec::new()));
+        let reported = missing.clone();
+        let config = EmbeddedBackendConfig {
+            font_regular: embedded_graphics::mono_font::ascii::FONT_6X10.into(),
+            missing_glyph_callback: Some(Box::new(move |c| reported.borrow_mut().push(c))),
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+
+        let cells = ["é", "a", "─", "é", "ż"].map(Cell::new);
+        backend
+            .draw(
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(x, cell)| (x as u16, 0, cell)),
+            )
+            .unwrap();
+        backend.flush().unwrap();
+        assert_eq!(*missing.borrow(), ['é', 'ż']);
+
+        backend.flush().unwrap();
+        assert_eq!(missing.borrow().len(), 2);
+    }
+
+    #[rstest]
+    fn skips_continuation_of_wide_cell(mut display: MockDisplay<Rgb888>) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+
+        let mut wide = Cell::new("日");
+        wide.set_bg(Color::White);
+        let continuation = Cell::new(" ");
+        backend
+            .draw([(0, 0, &wide), (1, 0, &continuation), (2, 0, &continuation)].into_iter())
+            .unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(display.get_pixel(Point::new(11, 0)), Some(Rgb888::WHITE));
+        assert_eq!(display.get_pixel(Point::new(12, 0)), Some(Rgb888::BLACK));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct BusTimeout;
+
+    /// Display whose bus always times out.
+    struct FailingDisplay;
+
+    impl OriginDimensions for FailingDisplay {
+        fn size(&self) -> Size {
+            Size::new(12, 10)
+        }
+    }
+
+    impl DrawTarget for FailingDisplay {
+        type Color = Rgb888;
+        type Error = BusTimeout;
+
+        fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            Err(BusTimeout)
+        }
+    }
+
+    #[rstest]
+    fn flush_returns_display_error() {
+        let mut display = FailingDisplay;
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+
+        let Err(crate::error::Error::DrawError(error)) = backend.flush() else {
+            panic!("flush didn't fail");
+        };
+        assert_eq!(error.downcast_ref(), Some(&BusTimeout));
+    }
+
+    #[rstest]
+    fn flush_returns_callback_error(mut display: MockDisplay<Rgb888>) {
+        let config = EmbeddedBackendConfig {
+            flush_callback: Box::new(|_| Err(DrawTargetError::new(BusTimeout))),
+            ..Default::default()
+        };
+        let mut backend = EmbeddedBackend::new(&mut display, config);
+
+        let Err(crate::error::Error::FlushError(error)) = backend.flush() else {
+            panic!("flush didn't fail");
+        };
+        assert!(error.is::<BusTimeout>());
+    }
+
+    /// Returns rows of the 10x6 grid, with `#` for white and `.` for cleared cells.
+    fn grid_lines(display: &MockDisplay<Rgb888>) -> Vec<alloc::string::String> {
+        (0..6)
+            .map(|y| {
+                (0..10)
+                    .map(|x| match display.get_pixel(Point::new(x * 6, y * 10)) {
+                        Some(Rgb888::WHITE) => '#',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(ClearType::AfterCursor, (3, 2), [
+        "##########",
+        "##########",
+        "####......",
+        "..........",
+        "..........",
+        "..........",
+    ])]
+    #[case(ClearType::BeforeCursor, (5, 3), [
+        "..........",
+        "..........",
+        "..........",
+        ".....#####",
+        "##########",
+        "##########",
+    ])]
+    #[case(ClearType::CurrentLine, (3, 1), [
+        "##########",
+        "..........",
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+    ])]
+    #[case(ClearType::UntilNewLine, (3, 0), [
+        "###.......",
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+    ])]
+    #[case(ClearType::AfterCursor, (9, 5), [
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+        "##########",
+    ])]
+    #[case(ClearType::BeforeCursor, (20, 20), [
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+    ])]
+    fn clears_region_around_cursor(
+        mut display: MockDisplay<Rgb888>,
+        #[case] clear_type: ClearType,
+        #[case] cursor: (u16, u16),
+        #[case] expected: [&str; 6],
+    ) {
+        let mut backend = EmbeddedBackend::new(&mut display, EmbeddedBackendConfig::default());
+        let cell = white_cell();
+        backend
+            .draw(
+                (0..6)
+                    .flat_map(|y| (0..10).map(move |x| (x, y)))
+                    .map(|(x, y)| (x, y, &cell)),
+            )
+            .unwrap();
+
+        backend.set_cursor_position(cursor).unwrap();
+        assert_eq!(
+            backend.get_cursor_position().unwrap(),
+            layout::Position::from(cursor)
+        );
+        backend.clear_region(clear_type).unwrap();
+        backend.flush().unwrap();
+        drop(backend);
+
+        assert_eq!(grid_lines(&display), expected);
+    }
+}
@@ -0,0 +1,219 @@
+//! Cache of rasterised glyphs.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use ratatui_core::style::Modifier;
+
+/// Coverage of the pixels of a glyph area, in row-major order,
+/// from 0 for the background to 255 for the foreground color.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Mask {
+    pub(crate) size: Size,
+    pub(crate) coverage: Vec<u8>,
+}
+
+impl Mask {
+    /// Clears the mask and resizes it to `size`.
+    fn reset(&mut self, size: Size) {
+        self.size = size;
+        self.coverage.clear();
+        self.coverage.resize((size.width * size.height) as usize, 0);
+    }
+
+    /// Covers `area` by at least `alpha`, clipped to the mask.
+    pub(crate) fn paint(&mut self, area: Rectangle, alpha: u8) {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        let width = self.size.width as usize;
+        for Point { x, y } in area.points() {
+            let coverage = &mut self.coverage[y as usize * width + x as usize];
+            *coverage = (*coverage).max(alpha);
+        }
+    }
+}
+
+struct Entry {
+    mask: Mask,
+    last_used: u64,
+}
+
+/// Least recently used cache of glyph masks, keyed by the symbol and face of the cell.
+///
+/// Masks are independent of the cell colors, so they are shared by all displays.
+pub(crate) struct GlyphCache {
+    budget: usize,
+    used: usize,
+    tick: u64,
+    /// Entries of regular, bold, italic and bold italic cells.
+    entries: [BTreeMap<String, Entry>; 4],
+    /// Face and symbol of every entry, by the tick it was last used at.
+    recency: BTreeMap<u64, (usize, String)>,
+    /// Mask of glyphs not cached.
+    scratch: Mask,
+}
+
+impl GlyphCache {
+    /// Creates a cache holding masks of up to `budget` bytes in total.
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            used: 0,
+            tick: 0,
+            entries: Default::default(),
+            recency: BTreeMap::new(),
+            scratch: Mask::default(),
+        }
+    }
+
+    /// Removes all masks, e.g. after the fonts change.
+    pub(crate) fn clear(&mut self) {
+        self.entries.iter_mut().for_each(BTreeMap::clear);
+        self.recency.clear();
+        self.used = 0;
+    }
+
+    /// Returns the mask of `size` for `symbol` in a cell with `modifier`,
+    /// rasterised by `rasterize` unless it is cached.
+    pub(crate) fn mask(
+        &mut self,
+        symbol: &str,
+        modifier: Modifier,
+        size: Size,
+        rasterize: impl FnOnce(&mut Mask),
+    ) -> &Mask {
+        self.tick += 1;
+        let face = modifier.contains(Modifier::BOLD) as usize
+            | (modifier.contains(Modifier::ITALIC) as usize) << 1;
+
+        if let Some(entry) = self.entries[face].get_mut(symbol) {
+            let key = self.recency.remove(&entry.last_used).unwrap();
+            self.recency.insert(self.tick, key);
+            entry.last_used = self.tick;
+            return &self.entries[face][symbol].mask;
+        }
+
+        self.scratch.reset(size);
+        rasterize(&mut self.scratch);
+
+        let cost = entry_cost(symbol, &self.scratch);
+        if cost > self.budget {
+            return &self.scratch;
+        }
+        while self.used + cost > self.budget {
+            self.evict();
+        }
+        self.used += cost;
+        self.recency.insert(self.tick, (face, String::from(symbol)));
+        let entry = Entry {
+            mask: self.scratch.clone(),
+            last_used: self.tick,
+        };
+        &self.entries[face]
+            .entry(String::from(symbol))
+            .or_insert(entry)
+            .mask
+    }
+
+    /// Removes the least recently used mask.
+    fn evict(&mut self) {
+        if let Some((_, (face, symbol))) = self.recency.pop_first() {
+            let entry = self.entries[face].remove(&symbol).unwrap();
+            self.used -= entry_cost(&symbol, &entry.mask);
+        }
+    }
+}
+
+/// Approximate memory used by the cache entry of `mask` for `symbol`, in bytes.
+fn entry_cost(symbol: &str, mask: &Mask) -> usize {
+    mask.coverage.len()
+        + 2 * symbol.len()
+        + core::mem::size_of::<Entry>()
+        + core::mem::size_of::<(u64, usize, String)>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const SIZE: Size = Size::new(2, 2);
+
+    /// Budget for `count` masks of single-byte symbols.
+    fn budget(count: usize) -> usize {
+        let mut mask = Mask::default();
+        mask.reset(SIZE);
+        count * entry_cost("a", &mask)
+    }
+
+    fn rasterize(alpha: u8) -> impl FnOnce(&mut Mask) {
+        move |mask: &mut Mask| mask.paint(Rectangle::new(Point::zero(), Size::new(1, 1)), alpha)
+    }
+
+    #[rstest]
+    fn paints_clipped_coverage() {
+        let mut mask = Mask::default();
+        mask.reset(Size::new(3, 2));
+        mask.paint(Rectangle::new(Point::new(1, 1), Size::new(4, 4)), 100);
+        mask.paint(Rectangle::new(Point::new(2, 0), Size::new(1, 2)), 50);
+        assert_eq!(mask.coverage, [0, 0, 50, 0, 100, 100]);
+    }
+
+    #[rstest]
+    fn reuses_cached_masks() {
+        let mut cache = GlyphCache::new(budget(2));
+        cache.mask("a", Modifier::empty(), SIZE, rasterize(1));
+        let mask = cache.mask("a", Modifier::empty(), SIZE, |_| panic!("rasterised again"));
+        assert_eq!(mask.coverage, [1, 0, 0, 0]);
+    }
+
+    #[rstest]
+    fn keys_masks_by_face() {
+        let mut cache = GlyphCache::new(budget(2));
+        cache.mask("a", Modifier::empty(), SIZE, rasterize(1));
+        let mask = cache.mask("a", Modifier::BOLD, SIZE, rasterize(2));
+        assert_eq!(mask.coverage, [2, 0, 0, 0]);
+    }
+
+    #[rstest]
+    fn evicts_least_recently_used_masks() {
+        let mut cache = GlyphCache::new(budget(2));
+        cache.mask("a", Modifier::empty(), SIZE, rasterize(1));
+        cache.mask("b", Modifier::empty(), SIZE, rasterize(2));
+        cache.mask("a", Modifier::empty(), SIZE, rasterize(3));
+        cache.mask("c", Modifier::empty(), SIZE, rasterize(4));
+
+        assert!(cache.entries[0].contains_key("a"));
+        assert!(!cache.entries[0].contains_key("b"));
+        assert!(cache.entries[0].contains_key("c"));
+        assert_eq!(cache.used, budget(2));
+    }
+
+    #[rstest]
+    fn skips_masks_over_budget() {
+        let mut cache = GlyphCache::new(0);
+        let mask = cache.mask("a", Modifier::empty(), SIZE, rasterize(1));
+        assert_eq!(mask.coverage, [1, 0, 0, 0]);
+        assert!(cache.entries[0].is_empty());
+    }
+
+    #[rstest]
+    fn evicts_repeatedly_past_budget() {
+        let mut cache = GlyphCache::new(budget(3));
+        for round in 0..4 {
+            for c in 'a'..='z' {
+                let symbol = alloc::string::ToString::to_string(&c);
+                let mask = cache.mask(&symbol, Modifier::empty(), SIZE, rasterize(c as u8));
+                assert_eq!(mask.coverage[0], c as u8, "round {round}");
+                assert!(cache.used <= budget(3));
+            }
+        }
+
+        assert_eq!(cache.used, budget(3));
+        assert_eq!(cache.recency.len(), 3);
+        let cached: Vec<&str> = cache.entries[0].keys().map(String::as_str).collect();
+        assert_eq!(cached, ["x", "y", "z"]);
+    }
+}
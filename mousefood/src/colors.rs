@@ -1,6 +1,8 @@
 use crate::macros::for_all_rgb_colors;
+use embedded_graphics::pixelcolor::raw::RawData;
 use embedded_graphics::pixelcolor::{
-    Bgr555, Bgr565, Bgr666, Bgr888, BinaryColor, Rgb555, Rgb565, Rgb666, Rgb888, RgbColor,
+    Bgr555, Bgr565, Bgr666, Bgr888, BinaryColor, PixelColor, Rgb555, Rgb565, Rgb666, Rgb888,
+    RgbColor,
 };
 use ratatui_core::style::Color;
 
@@ -84,6 +86,59 @@ impl From<TermColor> for BinaryColor {
     }
 }
 
+/// Returns a brighter variant of `color`, used for emboldened text.
+///
+/// Named colors map to their light variants, like bold text in many terminals,
+/// and RGB colors are moved a third of the way towards white.
+pub(crate) fn brighten(color: Color) -> Color {
+    match color {
+        Color::Black => Color::DarkGray,
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightBlue,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        Color::Gray | Color::DarkGray => Color::White,
+        Color::Rgb(r, g, b) => {
+            let brighten = |c: u8| c + (u8::MAX - c) / 3;
+            Color::Rgb(brighten(r), brighten(g), brighten(b))
+        }
+        color => color,
+    }
+}
+
+/// Returns the color of a glyph pixel covering `alpha` of 255 parts of a pixel,
+/// mixing `fg` over `bg`, or `None` if the pixel keeps the background.
+///
+/// Colors of one bit per pixel, such as [`BinaryColor`], have no shades in between,
+/// so the foreground is drawn where at least half of the pixel is covered.
+pub(crate) fn blend<C>(fg: C, bg: C, alpha: u8) -> Option<C>
+where
+    C: PixelColor + Into<Rgb888> + From<Rgb888>,
+{
+    match alpha {
+        0 => None,
+        u8::MAX => Some(fg),
+        _ if C::Raw::BITS_PER_PIXEL == 1 => (alpha >= 128).then_some(fg),
+        _ => {
+            let (fg, bg): (Rgb888, Rgb888) = (fg.into(), bg.into());
+            let mix = |fg: u8, bg: u8| {
+                let (fg, bg, alpha) = (fg as u32, bg as u32, alpha as u32);
+                ((fg * alpha + bg * (255 - alpha) + 127) / 255) as u8
+            };
+            Some(
+                Rgb888::new(
+                    mix(fg.r(), bg.r()),
+                    mix(fg.g(), bg.g()),
+                    mix(fg.b(), bg.b()),
+                )
+                .into(),
+            )
+        }
+    }
+}
+
 #[cfg(feature = "epd-weact")]
 impl From<TermColor> for weact_studio_epd::Color {
     fn from(color: TermColor) -> Self {
@@ -182,6 +237,37 @@ mod tests {
         assert_eq!(output, color_into);
     }
 
+    #[rstest]
+    #[case(Black, DarkGray)]
+    #[case(Red, LightRed)]
+    #[case(LightRed, LightRed)]
+    #[case(Gray, White)]
+    #[case(White, White)]
+    #[case(Reset, Reset)]
+    #[case(Rgb(0, 150, 255), Rgb(85, 185, 255))]
+    fn brighten_color(#[case] color: Color, #[case] expected: Color) {
+        assert_eq!(brighten(color), expected);
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(255, Some(Rgb888::new(200, 100, 0)))]
+    #[case(128, Some(Rgb888::new(125, 75, 25)))]
+    #[case(64, Some(Rgb888::new(88, 63, 37)))]
+    fn blend_mixes_colors(#[case] alpha: u8, #[case] expected: Option<Rgb888>) {
+        let (fg, bg) = (Rgb888::new(200, 100, 0), Rgb888::new(50, 50, 50));
+        assert_eq!(blend(fg, bg, alpha), expected);
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(127, None)]
+    #[case(128, Some(BinaryColor::On))]
+    #[case(255, Some(BinaryColor::On))]
+    fn blend_thresholds_binary_colors(#[case] alpha: u8, #[case] expected: Option<BinaryColor>) {
+        assert_eq!(blend(BinaryColor::On, BinaryColor::Off, alpha), expected);
+    }
+
     #[cfg(feature = "epd-weact")]
     #[rstest]
     #[case(Foreground, Black, weact_studio_epd::Color::Black)]
@@ -1,13 +1,158 @@
 //! Mousefood `Error` enum.
 
+use alloc::boxed::Box;
+use core::any::Any;
+use core::fmt;
+
 /// Represents backend error.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     /// Drawing to the display failed.
+    ///
+    /// The error returned by the [`DrawTarget`](embedded_graphics::draw_target::DrawTarget)
+    /// is available as the [`source`](core::error::Error::source) of this error.
     #[error("drawing to DrawTarget failed")]
-    DrawError,
+    DrawError(#[source] DrawTargetError),
+
+    /// The flush callback of a display failed.
+    ///
+    /// The error returned by the callback is available as the
+    /// [`source`](core::error::Error::source) of this error.
+    #[error("flush callback failed")]
+    FlushError(#[source] DrawTargetError),
+
+    /// The terminal cells are empty or don't fit on the displays,
+    /// e.g. because of the [`scale`](crate::EmbeddedBackendConfig::scale).
+    #[error("terminal cells don't fit on the display")]
+    InvalidCellSize,
+}
+
+impl Error {
+    /// Wraps an error returned by a display.
+    pub(crate) fn draw<E: Any + Send + Sync>(error: E) -> Self {
+        Self::DrawError(DrawTargetError::new(error))
+    }
+}
+
+/// Error returned by a [`DrawTarget`](embedded_graphics::draw_target::DrawTarget)
+/// or a flush callback, such as an SPI or I2C bus error of a display driver.
+///
+/// Display drivers don't share an error type, and aren't required to implement
+/// [`Debug`](fmt::Debug), so the error is kept as is and can be inspected
+/// with [`downcast_ref`](Self::downcast_ref).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use mousefood::error::Error;
+///
+/// if let Err(Error::DrawError(error)) = terminal.flush() {
+///     if let Some(DisplayError::BusWriteError) = error.downcast_ref() {
+///         // retry
+///     }
+/// }
+/// ```
+pub struct DrawTargetError {
+    error: Box<dyn Any + Send + Sync>,
+    type_name: &'static str,
+}
+
+impl DrawTargetError {
+    /// Wraps `error`, e.g. to return it from a flush callback.
+    ///
+    /// The error must be [`Send`] and [`Sync`], so that [`Error`] can be converted
+    /// into thread-safe error types.
+    pub fn new<E: Any + Send + Sync>(error: E) -> Self {
+        Self {
+            error: Box::new(error),
+            type_name: core::any::type_name::<E>(),
+        }
+    }
+
+    /// Returns whether the error is of type `E`.
+    pub fn is<E: Any>(&self) -> bool {
+        self.error.is::<E>()
+    }
+
+    /// Returns a reference to the error, if it is of type `E`.
+    pub fn downcast_ref<E: Any>(&self) -> Option<&E> {
+        self.error.downcast_ref()
+    }
+
+    /// Returns the error, if it is of type `E`.
+    pub fn downcast<E: Any>(self) -> Result<E, Self> {
+        let type_name = self.type_name;
+        self.error
+            .downcast()
+            .map(|error| *error)
+            .map_err(|error| Self { error, type_name })
+    }
+
+    /// Returns the name of the type of the error.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Debug for DrawTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DrawTargetError")
+            .field(&self.type_name)
+            .finish()
+    }
+}
+
+impl fmt::Display for DrawTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} returned by display", self.type_name)
+    }
+}
+
+impl core::error::Error for DrawTargetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::error::Error as _;
+    use rstest::rstest;
+
+    #[derive(Debug, PartialEq)]
+    enum BusError {
+        Timeout,
+    }
+
+    #[rstest]
+    fn error_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Error>();
+    }
+
+    #[rstest]
+    fn draw_error_keeps_source() {
+        let error = Error::draw(BusError::Timeout);
+        assert_eq!(error.to_string(), "drawing to DrawTarget failed");
+
+        let source = error.source().unwrap();
+        assert_eq!(
+            source.to_string(),
+            "mousefood::error::tests::BusError returned by display"
+        );
+        let Error::DrawError(source) = error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert!(source.is::<BusError>());
+        assert_eq!(source.downcast_ref(), Some(&BusError::Timeout));
+        assert_eq!(source.downcast::<BusError>().unwrap(), BusError::Timeout);
+    }
 
-    /// Selected [`ClearType`](ratatui_core::backend::ClearType) is not supported by Mousefood.
-    #[error("ClearType::{0} is not supported by Mousefood")]
-    ClearTypeUnsupported(alloc::string::String),
+    #[rstest]
+    fn downcast_to_other_type_fails() {
+        let Error::DrawError(source) = Error::draw(BusError::Timeout) else {
+            unreachable!();
+        };
+        assert!(!source.is::<u8>());
+        assert_eq!(source.downcast_ref::<u8>(), None);
+        assert!(source.downcast::<u8>().is_err());
+    }
 }
@@ -0,0 +1,531 @@
+//! Glyph sources the terminal is drawn with.
+
+use alloc::boxed::Box;
+use core::ops::Deref;
+
+use embedded_graphics::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics::image::GetPixel;
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+use embedded_graphics::mono_font::{DecorationDimensions, MonoFont};
+use embedded_graphics::pixelcolor::BinaryColor;
+
+/// Source of monospaced bitmap glyphs.
+///
+/// Implement this trait to draw the terminal with fonts in formats other than
+/// [`MonoFont`], e.g. with compressed glyph storage. Glyphs are drawn into
+/// the terminal cells, so pixels outside of the [character size](Self::character_size)
+/// are clipped. Anti-aliased fonts can be drawn with [`AlphaFont`].
+pub trait GlyphSource {
+    /// Size of the glyphs in pixels.
+    fn character_size(&self) -> Size;
+
+    /// Row of the baseline, counted from the top of the glyphs.
+    fn baseline(&self) -> u32;
+
+    /// Position and thickness of the underline.
+    fn underline(&self) -> DecorationDimensions;
+
+    /// Position and thickness of the strikethrough line.
+    fn strikethrough(&self) -> DecorationDimensions;
+
+    /// Returns whether the source has a glyph for `c`.
+    ///
+    /// Characters without a glyph are looked up in the fallback fonts.
+    /// The implementations for [`MonoFont`] and [`AlphaFont`] infer this from the glyph
+    /// mapping, which can't tell a regular glyph stored at the index of the replacement glyph
+    /// from a missing character. See [`Font::with_characters`] for an exact lookup.
+    fn has_glyph(&self, c: char) -> bool;
+
+    /// Glyph mapping of the source, if its missing characters are mapped
+    /// to a replacement glyph.
+    ///
+    /// A [`Font`] drawn from a source with a glyph mapping finds the replacement glyph once,
+    /// and answers [`has_glyph`](Self::has_glyph) from the mapping.
+    fn glyph_mapping(&self) -> Option<&dyn GlyphMapping> {
+        None
+    }
+
+    /// Calls `pixel` with the position of every foreground pixel of the glyph for `c`,
+    /// relative to the top left corner of the glyph, and its coverage.
+    ///
+    /// The coverage ranges from 0, leaving the background, to 255 for the foreground color.
+    /// Partially covered pixels of anti-aliased glyphs are blended between both colors.
+    /// Characters without a glyph are drawn with the replacement glyph of the source, if any.
+    fn draw_glyph(&self, c: char, pixel: &mut dyn FnMut(Point, u8));
+}
+
+impl GlyphSource for MonoFont<'_> {
+    fn character_size(&self) -> Size {
+        self.character_size
+    }
+
+    fn baseline(&self) -> u32 {
+        self.baseline
+    }
+
+    fn underline(&self) -> DecorationDimensions {
+        self.underline
+    }
+
+    fn strikethrough(&self) -> DecorationDimensions {
+        self.strikethrough
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        maps_glyph(
+            Replacement::find(self, self.glyph_mapping),
+            self.glyph_mapping,
+            c,
+        )
+    }
+
+    fn glyph_mapping(&self) -> Option<&dyn GlyphMapping> {
+        Some(self.glyph_mapping)
+    }
+
+    fn draw_glyph(&self, c: char, pixel: &mut dyn FnMut(Point, u8)) {
+        let Some(top_left) = glyph_position(
+            self.glyph_mapping.index(c),
+            self.character_size,
+            self.image.size().width,
+        ) else {
+            return;
+        };
+        for y in 0..self.character_size.height as i32 {
+            for x in 0..self.character_size.width as i32 {
+                let point = Point::new(x, y);
+                if self.image.pixel(top_left + point) == Some(BinaryColor::On) {
+                    pixel(point, u8::MAX);
+                }
+            }
+        }
+    }
+}
+
+/// Font with anti-aliased glyphs, storing the coverage of every pixel.
+///
+/// The glyphs are laid out like those of [`MonoFont`]: in rows of glyphs
+/// filling the width of the image, in the order given by the glyph mapping.
+/// Pixels are packed with the most significant bits first,
+/// and every row of the image starts at a new byte.
+///
+/// Glyph pixels are blended between the foreground and background colors of the cell.
+/// On displays with one bit per pixel, such as [`BinaryColor`] displays,
+/// pixels covered by at least half are drawn with the foreground color.
+#[derive(Clone, Copy)]
+pub struct AlphaFont<'a> {
+    image: &'a [u8],
+    image_width: u32,
+    bits_per_pixel: u8,
+    glyph_mapping: &'a dyn GlyphMapping,
+    character_size: Size,
+    baseline: u32,
+    underline: DecorationDimensions,
+    strikethrough: DecorationDimensions,
+}
+
+impl<'a> AlphaFont<'a> {
+    /// Creates a font from the glyph `image`, `image_width` pixels wide,
+    /// with `bits_per_pixel` bits of coverage per pixel.
+    ///
+    /// `baseline` is the row of the baseline, counted from the top of the glyphs,
+    /// and `underline` and `strikethrough` the position and thickness of the decoration lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits_per_pixel` is not 1, 2, 4 or 8.
+    /// For fonts defined as constants, this is a compile-time error.
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        image: &'a [u8],
+        image_width: u32,
+        bits_per_pixel: u8,
+        glyph_mapping: &'a dyn GlyphMapping,
+        character_size: Size,
+        baseline: u32,
+        underline: DecorationDimensions,
+        strikethrough: DecorationDimensions,
+    ) -> Self {
+        assert!(
+            matches!(bits_per_pixel, 1 | 2 | 4 | 8),
+            "bits_per_pixel must be 1, 2, 4 or 8"
+        );
+        Self {
+            image,
+            image_width,
+            bits_per_pixel,
+            glyph_mapping,
+            character_size,
+            baseline,
+            underline,
+            strikethrough,
+        }
+    }
+
+    /// Returns the coverage of the pixel at `point` of the image.
+    fn coverage(&self, point: Point) -> Option<u8> {
+        let bits = self.bits_per_pixel as u32;
+        if point.x as u32 >= self.image_width {
+            return None;
+        }
+        let row_bytes = (self.image_width * bits).div_ceil(8);
+        let bit = point.x as u32 * bits;
+        let byte = self
+            .image
+            .get((point.y as u32 * row_bytes + bit / 8) as usize)?;
+        let max = (1 << bits) - 1;
+        let value = (*byte as u32 >> (8 - bits - bit % 8)) & max;
+        Some((value * u8::MAX as u32 / max) as u8)
+    }
+}
+
+impl GlyphSource for AlphaFont<'_> {
+    fn character_size(&self) -> Size {
+        self.character_size
+    }
+
+    fn baseline(&self) -> u32 {
+        self.baseline
+    }
+
+    fn underline(&self) -> DecorationDimensions {
+        self.underline
+    }
+
+    fn strikethrough(&self) -> DecorationDimensions {
+        self.strikethrough
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        maps_glyph(
+            Replacement::find(self, self.glyph_mapping),
+            self.glyph_mapping,
+            c,
+        )
+    }
+
+    fn glyph_mapping(&self) -> Option<&dyn GlyphMapping> {
+        Some(self.glyph_mapping)
+    }
+
+    fn draw_glyph(&self, c: char, pixel: &mut dyn FnMut(Point, u8)) {
+        let Some(top_left) = glyph_position(
+            self.glyph_mapping.index(c),
+            self.character_size,
+            self.image_width,
+        ) else {
+            return;
+        };
+        for y in 0..self.character_size.height as i32 {
+            for x in 0..self.character_size.width as i32 {
+                let point = Point::new(x, y);
+                match self.coverage(top_left + point) {
+                    Some(0) | None => {}
+                    Some(alpha) => pixel(point, alpha),
+                }
+            }
+        }
+    }
+}
+
+/// Glyph that missing characters are mapped to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Replacement {
+    /// Index of the glyph.
+    index: usize,
+    /// Character the glyph depicts, if it is `?` or `�`.
+    character: Option<char>,
+}
+
+impl Replacement {
+    /// Finds the replacement glyph of `source`, drawn through `mapping`.
+    ///
+    /// [`GlyphMapping`] doesn't expose the replacement glyph, so it is found by looking up
+    /// two noncharacters no font contains. If they map to different glyphs, the mapping has
+    /// no single replacement glyph and `None` is returned. A replacement glyph that isn't blank
+    /// is taken to depict `?` or `�`, whichever of them is mapped to it.
+    fn find(source: &(impl GlyphSource + ?Sized), mapping: &dyn GlyphMapping) -> Option<Self> {
+        let index = mapping.index('\u{FFFF}');
+        if index != mapping.index('\u{FFFE}') {
+            return None;
+        }
+        let mut blank = true;
+        source.draw_glyph('\u{FFFF}', &mut |_, _| blank = false);
+        let character = ['?', '\u{FFFD}']
+            .into_iter()
+            .find(|&c| !blank && mapping.index(c) == index);
+        Some(Self { index, character })
+    }
+}
+
+/// Returns whether `mapping` has a glyph for `c`, instead of mapping it to the `replacement` glyph.
+///
+/// A regular glyph stored at the index of the replacement glyph is indistinguishable
+/// from it, so its character is considered missing.
+fn maps_glyph(replacement: Option<Replacement>, mapping: &dyn GlyphMapping, c: char) -> bool {
+    replacement.is_none_or(|replacement| {
+        Some(c) == replacement.character || mapping.index(c) != replacement.index
+    })
+}
+
+/// Returns whether `characters`, in the format of
+/// [`StrGlyphMapping`](embedded_graphics::mono_font::mapping::StrGlyphMapping), contain `c`.
+///
+/// A `\0` character is followed by the first and last characters of a range.
+fn lists_char(characters: &str, c: char) -> bool {
+    let mut chars = characters.chars();
+    while let Some(first) = chars.next() {
+        let found = match first {
+            '\0' => match (chars.next(), chars.next()) {
+                (Some(start), Some(end)) => (start..=end).contains(&c),
+                _ => false,
+            },
+            first => first == c,
+        };
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the top left corner of the glyph at `index` in an image `image_width` pixels wide,
+/// or `None` if the image is narrower than a glyph.
+fn glyph_position(index: usize, character_size: Size, image_width: u32) -> Option<Point> {
+    let glyphs_per_row = image_width.checked_div(character_size.width)?;
+    if glyphs_per_row == 0 {
+        return None;
+    }
+    let index = index as u32;
+    Some(Point::new(
+        (index % glyphs_per_row * character_size.width) as i32,
+        (index / glyphs_per_row * character_size.height) as i32,
+    ))
+}
+
+/// A font the terminal is drawn with.
+///
+/// Any [`GlyphSource`], including [`MonoFont`], converts into a `Font`:
+///
+/// ```rust
+/// use mousefood::Font;
+/// use mousefood::embedded_graphics::mono_font::ascii::FONT_6X10;
+///
+/// let font: Font = FONT_6X10.into();
+/// ```
+pub struct Font {
+    source: Box<dyn GlyphSource>,
+    replacement: Option<Replacement>,
+}
+
+impl Font {
+    /// Creates a font drawn from `source`.
+    pub fn new(source: impl GlyphSource + 'static) -> Self {
+        let replacement = source
+            .glyph_mapping()
+            .and_then(|mapping| Replacement::find(&source, mapping));
+        Self {
+            source: Box::new(source),
+            replacement,
+        }
+    }
+
+    /// Returns whether the font has a glyph for `c`.
+    ///
+    /// See [`GlyphSource::has_glyph`].
+    pub fn has_glyph(&self, c: char) -> bool {
+        match self.source.glyph_mapping() {
+            Some(mapping) => maps_glyph(self.replacement, mapping, c),
+            None => self.source.has_glyph(c),
+        }
+    }
+
+    /// Creates a font drawn from `source`, which has glyphs for exactly the `characters`.
+    ///
+    /// The characters are given in the format of
+    /// [`StrGlyphMapping`](embedded_graphics::mono_font::mapping::StrGlyphMapping),
+    /// with `\0` followed by the first and last characters of a range, so the string
+    /// of the font's mapping can be reused. This is needed for fonts whose replacement glyph
+    /// is also the glyph of a regular character, which would be considered missing otherwise.
+    ///
+    /// ```rust
+    /// use mousefood::Font;
+    /// use mousefood::embedded_graphics::mono_font::ascii::FONT_6X10;
+    ///
+    /// let font = Font::with_characters(FONT_6X10, "\0 ~");
+    /// ```
+    pub fn with_characters(source: impl GlyphSource + 'static, characters: &'static str) -> Self {
+        Self::new(Listed { source, characters })
+    }
+}
+
+/// Glyph source with an explicit list of characters.
+struct Listed<G> {
+    source: G,
+    characters: &'static str,
+}
+
+impl<G: GlyphSource> GlyphSource for Listed<G> {
+    fn character_size(&self) -> Size {
+        self.source.character_size()
+    }
+
+    fn baseline(&self) -> u32 {
+        self.source.baseline()
+    }
+
+    fn underline(&self) -> DecorationDimensions {
+        self.source.underline()
+    }
+
+    fn strikethrough(&self) -> DecorationDimensions {
+        self.source.strikethrough()
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        lists_char(self.characters, c)
+    }
+
+    fn draw_glyph(&self, c: char, pixel: &mut dyn FnMut(Point, u8)) {
+        self.source.draw_glyph(c, pixel)
+    }
+}
+
+impl<G: GlyphSource + 'static> From<G> for Font {
+    fn from(source: G) -> Self {
+        Self::new(source)
+    }
+}
+
+impl Deref for Font {
+    type Target = dyn GlyphSource;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use rstest::rstest;
+
+    use embedded_graphics::mono_font::mapping::StrGlyphMapping;
+    use embedded_graphics::mono_font::{ascii, iso_8859_1};
+
+    #[rstest]
+    #[case(ascii::FONT_6X10, 'a', true)]
+    #[case(ascii::FONT_6X10, '?', true)]
+    #[case(ascii::FONT_6X10, 'é', false)]
+    #[case(iso_8859_1::FONT_6X10, 'é', true)]
+    #[case(iso_8859_1::FONT_6X10, '─', false)]
+    fn has_glyph_detects_missing_glyphs(
+        #[case] font: MonoFont<'static>,
+        #[case] c: char,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(font.has_glyph(c), expected);
+    }
+
+    #[rstest]
+    #[case('l', &[(1, 1), (2, 1), (2, 2), (2, 3), (2, 4), (2, 5), (2, 6), (1, 7), (2, 7), (3, 7)])]
+    #[case(' ', &[])]
+    fn draws_mono_font_glyph(#[case] c: char, #[case] expected: &[(i32, i32)]) {
+        let mut pixels = Vec::new();
+        ascii::FONT_6X10.draw_glyph(c, &mut |point, alpha| {
+            assert_eq!(alpha, u8::MAX);
+            pixels.push((point.x, point.y));
+        });
+        assert_eq!(pixels, expected);
+    }
+
+    #[rstest]
+    #[case(2, &[0b0001_1011, 0b1110_0100], &[(1, 0, 85), (2, 0, 170), (3, 0, 255), (0, 1, 255), (1, 1, 170), (2, 1, 85)])]
+    #[case(4, &[0x0F, 0x80, 0x00, 0x01], &[(1, 0, 255), (2, 0, 136), (3, 1, 17)])]
+    fn draws_alpha_font_glyph(
+        #[case] bits_per_pixel: u8,
+        #[case] image: &[u8],
+        #[case] expected: &[(i32, i32, u8)],
+    ) {
+        // The replacement glyph is past the end of the image.
+        let mapping = StrGlyphMapping::new("a", 1);
+        let font = AlphaFont::new(
+            image,
+            4,
+            bits_per_pixel,
+            &mapping,
+            Size::new(4, 2),
+            1,
+            DecorationDimensions::default_underline(2),
+            DecorationDimensions::default_strikethrough(2),
+        );
+
+        assert!(font.has_glyph('a'));
+        let mut pixels = Vec::new();
+        font.draw_glyph('a', &mut |point, alpha| {
+            pixels.push((point.x, point.y, alpha))
+        });
+        assert_eq!(pixels, expected);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(3)]
+    #[case(16)]
+    #[should_panic(expected = "bits_per_pixel must be 1, 2, 4 or 8")]
+    fn alpha_font_rejects_unsupported_bits_per_pixel(#[case] bits_per_pixel: u8) {
+        let mapping = StrGlyphMapping::new("a", 1);
+        AlphaFont::new(
+            &[0],
+            4,
+            bits_per_pixel,
+            &mapping,
+            Size::new(4, 2),
+            1,
+            DecorationDimensions::default_underline(2),
+            DecorationDimensions::default_strikethrough(2),
+        );
+    }
+
+    /// Glyphs `A` to `Z`, with `A` doubling as the replacement glyph.
+    const ALIASED: StrGlyphMapping<'static> = StrGlyphMapping::new("\0AZ", 0);
+
+    #[rstest]
+    #[case('A', false)]
+    #[case('B', true)]
+    #[case('a', false)]
+    #[case('?', false)]
+    fn aliased_replacement_glyph_is_missing(#[case] c: char, #[case] expected: bool) {
+        // The first glyph of the image, ` `, is blank.
+        let font = Font::from(MonoFont {
+            glyph_mapping: &ALIASED,
+            ..ascii::FONT_6X10
+        });
+        assert_eq!(font.has_glyph(c), expected);
+    }
+
+    #[rstest]
+    #[case('?', true)]
+    #[case('\u{FFFD}', false)]
+    #[case('é', false)]
+    fn replacement_glyph_depicts_question_mark(#[case] c: char, #[case] expected: bool) {
+        let font = Font::from(ascii::FONT_6X10);
+        assert_eq!(
+            font.replacement.map(|replacement| replacement.character),
+            Some(Some('?'))
+        );
+        assert_eq!(font.has_glyph(c), expected);
+    }
+
+    #[rstest]
+    #[case('A', true)]
+    #[case('M', true)]
+    #[case('Z', true)]
+    #[case('a', false)]
+    #[case('\0', false)]
+    fn listed_characters_have_glyphs(#[case] c: char, #[case] expected: bool) {
+        let font = Font::with_characters(ascii::FONT_6X10, "\0AZ");
+        assert_eq!(font.has_glyph(c), expected);
+    }
+}
@@ -14,7 +14,7 @@ use embedded_graphics::Pixel;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::pixelcolor::PixelColor;
-use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::primitives::{PointsIter, Rectangle};
 use ratatui_core::style::Color;
 
 /// A heap-allocated framebuffer for storing pixels before rendering to a display.
@@ -104,6 +104,51 @@ impl<C: PixelColor> DrawTarget for HeapBuffer<C> {
         }
         Ok(())
     }
+
+    /// Fills the part of `area` within the framebuffer row by row.
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box);
+        let width = self.bounding_box.size.width as usize;
+        let columns = area.size.width as usize;
+        let offset = area.top_left - self.bounding_box.top_left;
+        for row in 0..area.size.height as usize {
+            let start = (offset.y as usize + row) * width + offset.x as usize;
+            self.data[start..start + columns].fill(color);
+        }
+        Ok(())
+    }
+
+    /// Fills `area` row by row, writing directly into the framebuffer
+    /// when the area lies within it.
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if area.intersection(&self.bounding_box) != *area {
+            let bounding_box = self.bounding_box;
+            return self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .filter(|(point, _)| bounding_box.contains(*point))
+                    .map(|(point, color)| Pixel(point, color)),
+            );
+        }
+
+        let width = self.bounding_box.size.width as usize;
+        let columns = area.size.width as usize;
+        let offset = area.top_left - self.bounding_box.top_left;
+        let mut colors = colors.into_iter();
+        for row in 0..area.size.height as usize {
+            let start = (offset.y as usize + row) * width + offset.x as usize;
+            for (pixel, color) in self.data[start..start + columns]
+                .iter_mut()
+                .zip(colors.by_ref())
+            {
+                *pixel = color;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +209,50 @@ mod tests {
         display.assert_pattern(expected);
     }
 
+    #[rstest]
+    #[case(Rectangle::new(Point::new(1, 1), Size::new(3, 2)))]
+    #[case(Rectangle::new(Point::new(14, 6), Size::new(4, 4)))]
+    #[case(Rectangle::new(Point::new(-2, -1), Size::new(3, 3)))]
+    fn fill_contiguous_matches_pixels(
+        mut heap_buffer: HeapBuffer<Rgb888>,
+        #[case] area: Rectangle,
+    ) {
+        let bounding_box = heap_buffer.bounding_box();
+        let mut expected = HeapBuffer::new(bounding_box);
+        let colors = (0..).map(|index| Rgb888::new(index, 0, 0));
+
+        heap_buffer.fill_contiguous(&area, colors.clone()).unwrap();
+        expected
+            .draw_iter(
+                area.points()
+                    .zip(colors)
+                    .filter(|(point, _)| bounding_box.contains(*point))
+                    .map(|(point, color)| Pixel(point, color)),
+            )
+            .unwrap();
+        assert!(heap_buffer.into_iter().eq(expected));
+    }
+
+    #[rstest]
+    #[case(Rectangle::new(Point::new(1, 1), Size::new(3, 2)))]
+    #[case(Rectangle::new(Point::new(14, 6), Size::new(4, 4)))]
+    #[case(Rectangle::new(Point::new(-2, -1), Size::new(3, 3)))]
+    #[case(Rectangle::new(Point::new(20, 20), Size::new(3, 3)))]
+    fn fill_solid_matches_pixels(mut heap_buffer: HeapBuffer<Rgb888>, #[case] area: Rectangle) {
+        let bounding_box = heap_buffer.bounding_box();
+        let mut expected = HeapBuffer::new(bounding_box);
+
+        heap_buffer.fill_solid(&area, Rgb888::RED).unwrap();
+        expected
+            .draw_iter(
+                area.points()
+                    .filter(|point| bounding_box.contains(*point))
+                    .map(|point| Pixel(point, Rgb888::RED)),
+            )
+            .unwrap();
+        assert!(heap_buffer.into_iter().eq(expected));
+    }
+
     #[rstest]
     fn test_heap_buffer_as_ref(
         mut display: MockDisplay<Rgb888>,
@@ -4,15 +4,24 @@
 extern crate alloc;
 
 mod backend;
+mod cache;
 mod colors;
 mod default_font;
 pub mod error;
+mod font;
 pub mod framebuffer;
 mod macros;
 pub mod prelude;
+mod procedural;
+mod render;
+mod target;
 
-pub use backend::{EmbeddedBackend, EmbeddedBackendConfig, TerminalAlignment};
+pub use backend::{
+    EmbeddedBackend, EmbeddedBackendConfig, FlushCallback, MirrorConfig, Padding, ReplacementGlyph,
+    TerminalAlignment, UnderlineStyle,
+};
 pub use embedded_graphics;
+pub use font::{AlphaFont, Font, GlyphSource};
 
 #[cfg(feature = "fonts")]
 pub use embedded_graphics_unicodefonts as fonts;
@@ -6,7 +6,12 @@
 //! use mousefood::prelude::*;
 //! ```
 
-pub use crate::backend::{EmbeddedBackend, EmbeddedBackendConfig};
+pub use crate::backend::{
+    EmbeddedBackend, EmbeddedBackendConfig, MirrorConfig, Padding, ReplacementGlyph,
+    TerminalAlignment, UnderlineStyle,
+};
+pub use crate::error::DrawTargetError;
+pub use crate::font::{AlphaFont, Font, GlyphSource};
 pub use embedded_graphics::pixelcolor::{
     Bgr555, Bgr565, Bgr666, Bgr888, Rgb555, Rgb565, Rgb666, Rgb888,
 };
@@ -0,0 +1,945 @@
+//! Procedural rendering of box-drawing, block-element, Braille and sextant characters,
+//! and of replacement glyphs for characters missing from the fonts.
+//!
+//! Glyphs are drawn as filled rectangles covering the whole cell, so lines join
+//! seamlessly across cells and the result does not depend on the configured font.
+
+use crate::backend::ReplacementGlyph;
+use embedded_graphics::Pixel;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::primitives::Rectangle;
+
+/// Draws `symbol` into `area` if it is one of the procedurally rendered characters.
+///
+/// Returns `false`, without drawing anything, if the symbol is not supported.
+pub(crate) fn draw<T>(
+    target: &mut T,
+    symbol: &str,
+    area: Rectangle,
+    fg: T::Color,
+    bg: T::Color,
+) -> Result<bool, T::Error>
+where
+    T: DrawTarget,
+{
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return Ok(false);
+    };
+    if !is_supported(c) {
+        return Ok(false);
+    }
+
+    target.fill_solid(&area, bg)?;
+    let mut canvas = Canvas {
+        target,
+        area,
+        color: fg,
+    };
+    match c {
+        '\u{2500}'..='\u{257F}' => canvas.box_drawing(c)?,
+        '\u{2580}'..='\u{2595}' => canvas.block_element(c)?,
+        '\u{2596}'..='\u{259F}' => canvas.quadrant(c)?,
+        '\u{2800}'..='\u{28FF}' => canvas.braille(c)?,
+        '\u{1FB00}'..='\u{1FB3B}' => canvas.sextant(c)?,
+        _ => unreachable!(),
+    }
+    Ok(true)
+}
+
+/// Returns whether `c` is drawn procedurally by [`draw`].
+pub(crate) fn is_supported(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2500}'..='\u{259F}' | '\u{2800}'..='\u{28FF}' | '\u{1FB00}'..='\u{1FB3B}'
+    )
+}
+
+/// Draws the replacement glyph for `c` into `area`.
+pub(crate) fn draw_replacement<T>(
+    target: &mut T,
+    replacement: ReplacementGlyph,
+    c: char,
+    area: Rectangle,
+    fg: T::Color,
+    bg: T::Color,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget,
+{
+    target.fill_solid(&area, bg)?;
+    let mut canvas = Canvas {
+        target,
+        area,
+        color: fg,
+    };
+    match replacement {
+        ReplacementGlyph::HollowBox => canvas.hollow_box(),
+        ReplacementGlyph::HexBox => canvas.hex_box(c),
+    }
+}
+
+/// Weight of a line leaving the center of a box-drawing cell.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Weight {
+    None,
+    Light,
+    Heavy,
+    Double,
+}
+
+/// Lines of the box-drawing characters U+2500–U+257F as `[up, right, down, left]`.
+///
+/// Dashed lines are listed with their solid counterpart, arcs and diagonals are drawn separately.
+const BOX_DRAWING: [[u8; 4]; 0x80] = [
+    [0, 1, 0, 1], // ─
+    [0, 2, 0, 2], // ━
+    [1, 0, 1, 0], // │
+    [2, 0, 2, 0], // ┃
+    [0, 1, 0, 1], // ┄
+    [0, 2, 0, 2], // ┅
+    [1, 0, 1, 0], // ┆
+    [2, 0, 2, 0], // ┇
+    [0, 1, 0, 1], // ┈
+    [0, 2, 0, 2], // ┉
+    [1, 0, 1, 0], // ┊
+    [2, 0, 2, 0], // ┋
+    [0, 1, 1, 0], // ┌
+    [0, 2, 1, 0], // ┍
+    [0, 1, 2, 0], // ┎
+    [0, 2, 2, 0], // ┏
+    [0, 0, 1, 1], // ┐
+    [0, 0, 1, 2], // ┑
+    [0, 0, 2, 1], // ┒
+    [0, 0, 2, 2], // ┓
+    [1, 1, 0, 0], // └
+    [1, 2, 0, 0], // ┕
+    [2, 1, 0, 0], // ┖
+    [2, 2, 0, 0], // ┗
+    [1, 0, 0, 1], // ┘
+    [1, 0, 0, 2], // ┙
+    [2, 0, 0, 1], // ┚
+    [2, 0, 0, 2], // ┛
+    [1, 1, 1, 0], // ├
+    [1, 2, 1, 0], // ┝
+    [2, 1, 1, 0], // ┞
+    [1, 1, 2, 0], // ┟
+    [2, 1, 2, 0], // ┠
+    [2, 2, 1, 0], // ┡
+    [1, 2, 2, 0], // ┢
+    [2, 2, 2, 0], // ┣
+    [1, 0, 1, 1], // ┤
+    [1, 0, 1, 2], // ┥
+    [2, 0, 1, 1], // ┦
+    [1, 0, 2, 1], // ┧
+    [2, 0, 2, 1], // ┨
+    [2, 0, 1, 2], // ┩
+    [1, 0, 2, 2], // ┪
+    [2, 0, 2, 2], // ┫
+    [0, 1, 1, 1], // ┬
+    [0, 1, 1, 2], // ┭
+    [0, 2, 1, 1], // ┮
+    [0, 2, 1, 2], // ┯
+    [0, 1, 2, 1], // ┰
+    [0, 1, 2, 2], // ┱
+    [0, 2, 2, 1], // ┲
+    [0, 2, 2, 2], // ┳
+    [1, 1, 0, 1], // ┴
+    [1, 1, 0, 2], // ┵
+    [1, 2, 0, 1], // ┶
+    [1, 2, 0, 2], // ┷
+    [2, 1, 0, 1], // ┸
+    [2, 1, 0, 2], // ┹
+    [2, 2, 0, 1], // ┺
+    [2, 2, 0, 2], // ┻
+    [1, 1, 1, 1], // ┼
+    [1, 1, 1, 2], // ┽
+    [1, 2, 1, 1], // ┾
+    [1, 2, 1, 2], // ┿
+    [2, 1, 1, 1], // ╀
+    [1, 1, 2, 1], // ╁
+    [2, 1, 2, 1], // ╂
+    [2, 1, 1, 2], // ╃
+    [2, 2, 1, 1], // ╄
+    [1, 1, 2, 2], // ╅
+    [1, 2, 2, 1], // ╆
+    [2, 2, 1, 2], // ╇
+    [1, 2, 2, 2], // ╈
+    [2, 1, 2, 2], // ╉
+    [2, 2, 2, 1], // ╊
+    [2, 2, 2, 2], // ╋
+    [0, 1, 0, 1], // ╌
+    [0, 2, 0, 2], // ╍
+    [1, 0, 1, 0], // ╎
+    [2, 0, 2, 0], // ╏
+    [0, 3, 0, 3], // ═
+    [3, 0, 3, 0], // ║
+    [0, 3, 1, 0], // ╒
+    [0, 1, 3, 0], // ╓
+    [0, 3, 3, 0], // ╔
+    [0, 0, 1, 3], // ╕
+    [0, 0, 3, 1], // ╖
+    [0, 0, 3, 3], // ╗
+    [1, 3, 0, 0], // ╘
+    [3, 1, 0, 0], // ╙
+    [3, 3, 0, 0], // ╚
+    [1, 0, 0, 3], // ╛
+    [3, 0, 0, 1], // ╜
+    [3, 0, 0, 3], // ╝
+    [1, 3, 1, 0], // ╞
+    [3, 1, 3, 0], // ╟
+    [3, 3, 3, 0], // ╠
+    [1, 0, 1, 3], // ╡
+    [3, 0, 3, 1], // ╢
+    [3, 0, 3, 3], // ╣
+    [0, 3, 1, 3], // ╤
+    [0, 1, 3, 1], // ╥
+    [0, 3, 3, 3], // ╦
+    [1, 3, 0, 3], // ╧
+    [3, 1, 0, 1], // ╨
+    [3, 3, 0, 3], // ╩
+    [1, 3, 1, 3], // ╪
+    [3, 1, 3, 1], // ╫
+    [3, 3, 3, 3], // ╬
+    [0, 1, 1, 0], // ╭
+    [0, 0, 1, 1], // ╮
+    [1, 0, 0, 1], // ╯
+    [1, 1, 0, 0], // ╰
+    [0, 0, 0, 0], // ╱
+    [0, 0, 0, 0], // ╲
+    [0, 0, 0, 0], // ╳
+    [0, 0, 0, 1], // ╴
+    [1, 0, 0, 0], // ╵
+    [0, 1, 0, 0], // ╶
+    [0, 0, 1, 0], // ╷
+    [0, 0, 0, 2], // ╸
+    [2, 0, 0, 0], // ╹
+    [0, 2, 0, 0], // ╺
+    [0, 0, 2, 0], // ╻
+    [0, 2, 0, 1], // ╼
+    [1, 0, 2, 0], // ╽
+    [0, 1, 0, 2], // ╾
+    [2, 0, 1, 0], // ╿
+];
+
+/// A run of pixels across a line: `(start, length)` relative to the cell.
+type Stroke = (i32, i32);
+
+/// Draw target restricted to a single cell, with coordinates relative to its top left corner.
+struct Canvas<'a, T: DrawTarget> {
+    target: &'a mut T,
+    area: Rectangle,
+    color: T::Color,
+}
+
+impl<T: DrawTarget> Canvas<'_, T> {
+    fn width(&self) -> i32 {
+        self.area.size.width as i32
+    }
+
+    fn height(&self) -> i32 {
+        self.area.size.height as i32
+    }
+
+    /// Fills a rectangle given in cell coordinates, clipped to the cell.
+    fn fill(&mut self, x: i32, y: i32, width: i32, height: i32) -> Result<(), T::Error> {
+        let x0 = x.clamp(0, self.width());
+        let y0 = y.clamp(0, self.height());
+        let x1 = (x + width).clamp(0, self.width());
+        let y1 = (y + height).clamp(0, self.height());
+        if x1 <= x0 || y1 <= y0 {
+            return Ok(());
+        }
+        self.target.fill_solid(
+            &Rectangle::new(
+                self.area.top_left + Point::new(x0, y0),
+                Size::new((x1 - x0) as u32, (y1 - y0) as u32),
+            ),
+            self.color,
+        )
+    }
+
+    /// Fills a rectangle whose axes are swapped when `vertical` is set.
+    fn fill_oriented(
+        &mut self,
+        vertical: bool,
+        along: i32,
+        across: i32,
+        length: i32,
+        thickness: i32,
+    ) -> Result<(), T::Error> {
+        if vertical {
+            self.fill(across, along, thickness, length)
+        } else {
+            self.fill(along, across, length, thickness)
+        }
+    }
+
+    /// Sets single pixels given in cell coordinates.
+    fn pixels(&mut self, points: impl Iterator<Item = Point>) -> Result<(), T::Error> {
+        let (area, color) = (self.area, self.color);
+        self.target.draw_iter(
+            points
+                .filter(|p| p.x >= 0 && p.x < area.size.width as i32)
+                .filter(|p| p.y >= 0 && p.y < area.size.height as i32)
+                .map(|p| Pixel(area.top_left + p, color)),
+        )
+    }
+
+    /// Thickness of a light line.
+    fn light(&self) -> i32 {
+        (self.width().min(self.height()) / 6).max(1)
+    }
+
+    /// Strokes making up a line of `weight`, centered across an extent of `size` pixels.
+    fn strokes(&self, weight: Weight, size: i32) -> ([Stroke; 2], usize) {
+        let light = self.light();
+        let centered = |thickness: i32| (size - thickness) / 2;
+        match weight {
+            Weight::None => ([(0, 0); 2], 0),
+            Weight::Light => ([(centered(light), light), (0, 0)], 1),
+            Weight::Heavy => ([(centered(2 * light), 2 * light), (0, 0)], 1),
+            Weight::Double => {
+                let start = centered(3 * light);
+                ([(start, light), (start + 2 * light, light)], 2)
+            }
+        }
+    }
+
+    fn box_drawing(&mut self, c: char) -> Result<(), T::Error> {
+        let [up, right, down, left] = BOX_DRAWING[c as usize - 0x2500].map(|weight| match weight {
+            1 => Weight::Light,
+            2 => Weight::Heavy,
+            3 => Weight::Double,
+            _ => Weight::None,
+        });
+
+        match c {
+            '┄' | '┅' | '┆' | '┇' => self.dashed(up != Weight::None, up.or(right), 3),
+            '┈' | '┉' | '┊' | '┋' => self.dashed(up != Weight::None, up.or(right), 4),
+            '╌' | '╍' | '╎' | '╏' => self.dashed(up != Weight::None, up.or(right), 2),
+            '╭' | '╮' | '╯' | '╰' => self.arc(c),
+            '╱' => self.diagonal(false),
+            '╲' => self.diagonal(true),
+            '╳' => {
+                self.diagonal(false)?;
+                self.diagonal(true)
+            }
+            _ => {
+                self.arms(false, [up, down], [left, right])?;
+                self.arms(true, [left, right], [up, down])
+            }
+        }
+    }
+
+    /// Draws the horizontal (or, if `vertical`, the vertical) arms of a box-drawing character.
+    ///
+    /// `sides` are the perpendicular arms, the ones before and after the line,
+    /// and `arms` are the arms to draw, towards the start and the end of the cell.
+    fn arms(
+        &mut self,
+        vertical: bool,
+        sides: [Weight; 2],
+        arms: [Weight; 2],
+    ) -> Result<(), T::Error> {
+        let (length, across) = if vertical {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+
+        // Strokes of the perpendicular arms, which the arms drawn here have to meet.
+        let side_strokes = sides.map(|side| self.strokes(side, length));
+        let hub = side_strokes
+            .iter()
+            .flat_map(|(strokes, count)| &strokes[..*count])
+            .fold(None, |hub: Option<Stroke>, &(start, len)| match hub {
+                None => Some((start, start + len)),
+                Some((min, max)) => Some((min.min(start), max.max(start + len))),
+            });
+
+        for (end, weight) in arms.into_iter().enumerate() {
+            let (strokes, count) = self.strokes(weight, across);
+            for (index, &(position, thickness)) in strokes[..count].iter().enumerate() {
+                // Double lines stop at the nearest stroke of a perpendicular arm on their side.
+                let (near_strokes, near_count) = side_strokes[index];
+                let (join_start, join_end) = match (weight, sides[index]) {
+                    (Weight::Double, Weight::Double) => {
+                        let (start, len) = near_strokes[end];
+                        (start, start + len)
+                    }
+                    (Weight::Double, side) if side != Weight::None && near_count > 0 => {
+                        let (start, len) = near_strokes[0];
+                        (start, start + len)
+                    }
+                    _ => hub.unwrap_or_else(|| {
+                        let start = (length - thickness) / 2;
+                        (start, start + thickness)
+                    }),
+                };
+                let (from, to) = if end == 0 {
+                    (0, join_end)
+                } else {
+                    (join_start, length)
+                };
+                self.fill_oriented(vertical, from, position, to - from, thickness)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a line of `count` dashes across the whole cell.
+    fn dashed(&mut self, vertical: bool, weight: Weight, count: i32) -> Result<(), T::Error> {
+        let (length, across) = if vertical {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+        let (strokes, _) = self.strokes(weight, across);
+        let (position, thickness) = strokes[0];
+
+        for dash in 0..count {
+            let from = dash * length / count;
+            let to = (dash + 1) * length / count;
+            let gap = ((to - from) / 2).min(to - from - 1).max(0);
+            self.fill_oriented(
+                vertical,
+                from + gap / 2,
+                position,
+                to - from - gap,
+                thickness,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draws a rounded corner joining the two light lines of `c`.
+    fn arc(&mut self, c: char) -> Result<(), T::Error> {
+        let light = self.light();
+        let (width, height) = (self.width(), self.height());
+        let x = (width - light) / 2;
+        let y = (height - light) / 2;
+
+        // Center of the circle and direction of the straight segments, in doubled coordinates.
+        let radius = (width.min(height) / 2)
+            .min(width - x - light)
+            .min(height - y - light);
+        let (dx, dy) = match c {
+            '╭' => (1, 1),
+            '╮' => (-1, 1),
+            '╯' => (-1, -1),
+            _ => (1, -1),
+        };
+        let center_x = 2 * x + light + dx * 2 * radius;
+        let center_y = 2 * y + light + dy * 2 * radius;
+
+        // Straight segments from the end of the arc to the edges of the cell.
+        let arc_x = center_x / 2 - if dx > 0 { 0 } else { light - 1 };
+        let arc_y = center_y / 2 - if dy > 0 { 0 } else { light - 1 };
+        if dx > 0 {
+            self.fill(arc_x, y, width - arc_x, light)?;
+        } else {
+            self.fill(0, y, arc_x + light, light)?;
+        }
+        if dy > 0 {
+            self.fill(x, arc_y, light, height - arc_y)?;
+        } else {
+            self.fill(x, 0, light, arc_y + light)?;
+        }
+
+        // Quarter of a ring, `light` pixels wide, between the two segments.
+        let inner = (2 * radius - light).max(0).pow(2);
+        let outer = (2 * radius + light).pow(2);
+        let (x0, x1) = (center_x / 2 - radius - light, center_x / 2 + radius + light);
+        let (y0, y1) = (center_y / 2 - radius - light, center_y / 2 + radius + light);
+        self.pixels(
+            (y0..=y1)
+                .flat_map(|py| (x0..=x1).map(move |px| Point::new(px, py)))
+                .filter(|p| {
+                    let (ox, oy) = (2 * p.x + 1 - center_x, 2 * p.y + 1 - center_y);
+                    let distance = ox * ox + oy * oy;
+                    ox * dx <= 0 && oy * dy <= 0 && distance >= inner && distance <= outer
+                }),
+        )
+    }
+
+    /// Draws a diagonal from corner to corner, descending to the right if `descending`.
+    fn diagonal(&mut self, descending: bool) -> Result<(), T::Error> {
+        let light = self.light();
+        let (width, height) = (self.width(), self.height());
+        for row in 0..height {
+            let from = row * width / height;
+            let to = ((row + 1) * width / height).max(from + light);
+            let x = if descending { from } else { width - to };
+            self.fill(x, row, to - from, 1)?;
+        }
+        Ok(())
+    }
+
+    fn block_element(&mut self, c: char) -> Result<(), T::Error> {
+        let (width, height) = (self.width(), self.height());
+        let eighths = |size: i32, n: i32| (size * n + 4) / 8;
+        match c {
+            '▀' => self.fill(0, 0, width, eighths(height, 4)),
+            '▁'..='█' => {
+                let top = height - eighths(height, c as i32 - '▀' as i32);
+                self.fill(0, top, width, height - top)
+            }
+            '▉'..='▏' => self.fill(0, 0, eighths(width, '▐' as i32 - c as i32), height),
+            '▐' => {
+                let left = eighths(width, 4);
+                self.fill(left, 0, width - left, height)
+            }
+            '░' | '▒' | '▓' => self.shade(c),
+            '▔' => self.fill(0, 0, width, eighths(height, 1)),
+            '▕' => {
+                let left = width - eighths(width, 1);
+                self.fill(left, 0, width - left, height)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fills the cell with a dither pattern anchored to the display, so it continues across cells.
+    fn shade(&mut self, c: char) -> Result<(), T::Error> {
+        let origin = self.area.top_left;
+        let (width, height) = (self.width(), self.height());
+        let set = move |p: &Point| {
+            let (x, y) = (origin.x + p.x, origin.y + p.y);
+            match c {
+                '░' => x % 2 == 0 && y % 2 == 0,
+                '▒' => (x + y) % 2 == 0,
+                _ => !(x % 2 == 1 && y % 2 == 1),
+            }
+        };
+        self.pixels(
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+                .filter(set),
+        )
+    }
+}
+
+/// Position of the dots of Braille patterns as `(column, row)`, in the order of their bits.
+const BRAILLE_DOTS: [(i32, i32); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (0, 3),
+    (1, 3),
+];
+
+/// Quadrants of the characters U+2596–U+259F, as bits of a 2×2 grid in row-major order.
+const QUADRANTS: [u8; 10] = [
+    0b0100, // ▖
+    0b1000, // ▗
+    0b0001, // ▘
+    0b1101, // ▙
+    0b1001, // ▚
+    0b0111, // ▛
+    0b1011, // ▜
+    0b0010, // ▝
+    0b0110, // ▞
+    0b1110, // ▟
+];
+
+impl<T: DrawTarget> Canvas<'_, T> {
+    /// Fills the parts of a `columns` × `rows` grid over the cell whose bits are set in `mask`,
+    /// in row-major order.
+    fn grid(&mut self, columns: i32, rows: i32, mask: u32) -> Result<(), T::Error> {
+        let (width, height) = (self.width(), self.height());
+        for row in 0..rows {
+            for column in 0..columns {
+                if mask & (1 << (row * columns + column)) == 0 {
+                    continue;
+                }
+                let (x0, x1) = (column * width / columns, (column + 1) * width / columns);
+                let (y0, y1) = (row * height / rows, (row + 1) * height / rows);
+                self.fill(x0, y0, x1 - x0, y1 - y0)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn quadrant(&mut self, c: char) -> Result<(), T::Error> {
+        self.grid(2, 2, QUADRANTS[c as usize - 0x2596] as u32)
+    }
+
+    /// Draws a sextant from the Symbols for Legacy Computing block.
+    ///
+    /// The characters enumerate the 2×3 patterns in binary order, skipping the empty
+    /// and full cell as well as the left and right half blocks, which are encoded elsewhere.
+    fn sextant(&mut self, c: char) -> Result<(), T::Error> {
+        let mut mask = c as u32 - 0x1FB00 + 1;
+        if mask >= 0b010101 {
+            mask += 1;
+        }
+        if mask >= 0b101010 {
+            mask += 1;
+        }
+        self.grid(2, 3, mask)
+    }
+
+    /// Draws a Braille pattern, with every dot filling its part of a 2×4 grid over the cell.
+    fn braille(&mut self, c: char) -> Result<(), T::Error> {
+        let (width, height) = (self.width(), self.height());
+        let dots = c as u32 - 0x2800;
+        for (bit, (column, row)) in BRAILLE_DOTS.into_iter().enumerate() {
+            if dots & (1 << bit) == 0 {
+                continue;
+            }
+            let (x0, x1) = (column * width / 2, (column + 1) * width / 2);
+            let (y0, y1) = (row * height / 4, (row + 1) * height / 4);
+            self.fill(x0, y0, x1 - x0, y1 - y0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Hexadecimal digits, 3×5 pixels each, as rows of 3 bits from top to bottom.
+const HEX_DIGITS: [u16; 16] = [
+    0b111_101_101_101_111, // 0
+    0b010_110_010_010_111, // 1
+    0b111_001_111_100_111, // 2
+    0b111_001_111_001_111, // 3
+    0b101_101_111_001_001, // 4
+    0b111_100_111_001_111, // 5
+    0b111_100_111_101_111, // 6
+    0b111_001_001_001_001, // 7
+    0b111_101_111_101_111, // 8
+    0b111_101_111_001_111, // 9
+    0b111_101_111_101_101, // A
+    0b110_101_110_101_110, // B
+    0b111_100_100_100_111, // C
+    0b110_101_101_101_110, // D
+    0b111_100_111_100_111, // E
+    0b111_100_111_100_100, // F
+];
+
+impl<T: DrawTarget> Canvas<'_, T> {
+    /// Draws the outline of a box inset by one pixel from the edges of the cell.
+    fn hollow_box(&mut self) -> Result<(), T::Error> {
+        let light = self.light();
+        let (width, height) = (self.width() - 2, self.height() - 2);
+        self.fill(1, 1, width, light)?;
+        self.fill(1, height + 1 - light, width, light)?;
+        self.fill(1, 1, light, height)?;
+        self.fill(width + 1 - light, 1, light, height)
+    }
+
+    /// Draws a box containing the code point of `c` as two columns of hexadecimal digits.
+    ///
+    /// Falls back to [`hollow_box`](Self::hollow_box) if the digits don't fit into the cell.
+    fn hex_box(&mut self, c: char) -> Result<(), T::Error> {
+        let code = c as u32;
+        let rows = if code > 0xFFFF { 3 } else { 2 };
+
+        // Digits with a pixel of space around them, inside the outline.
+        let digits_width = 2 * 3 + 1;
+        let digits_height = rows * 5 + (rows - 1);
+        let scale = ((self.width() - 4) / digits_width).min((self.height() - 4) / digits_height);
+        if scale < 1 {
+            return self.hollow_box();
+        }
+        self.hollow_box()?;
+
+        let x0 = (self.width() - digits_width * scale) / 2;
+        let y0 = (self.height() - digits_height * scale) / 2;
+        for index in 0..2 * rows {
+            let digit = (code >> (4 * (2 * rows - 1 - index))) & 0xF;
+            let x = x0 + (index % 2) * 4 * scale;
+            let y = y0 + (index / 2) * 6 * scale;
+            let bits = HEX_DIGITS[digit as usize];
+            for bit in 0..15 {
+                if bits & (1 << (14 - bit)) != 0 {
+                    self.fill(x + bit % 3 * scale, y + bit / 3 * scale, scale, scale)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Weight {
+    /// Returns `self`, or `other` if `self` is [`Weight::None`].
+    fn or(self, other: Weight) -> Weight {
+        if self == Weight::None { other } else { self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    fn render(symbol: &str, size: Size) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        let handled = draw(
+            &mut display,
+            symbol,
+            Rectangle::new(Point::zero(), size),
+            BinaryColor::On,
+            BinaryColor::Off,
+        )
+        .unwrap();
+        assert!(handled);
+        display
+    }
+
+    #[rstest]
+    #[case("─", &[
+        "......",
+        "......",
+        "......",
+        "######",
+        "......",
+        "......",
+        "......",
+    ])]
+    #[case("┼", &[
+        "..#...",
+        "..#...",
+        "..#...",
+        "######",
+        "..#...",
+        "..#...",
+        "..#...",
+    ])]
+    #[case("┏", &[
+        "......",
+        "......",
+        "..####",
+        "..####",
+        "..##..",
+        "..##..",
+        "..##..",
+    ])]
+    #[case("╭", &[
+        "......",
+        "......",
+        "......",
+        "....##",
+        "...#..",
+        "..#...",
+        "..#...",
+    ])]
+    #[case("╔", &[
+        "......",
+        "......",
+        ".#####",
+        ".#....",
+        ".#.###",
+        ".#.#..",
+        ".#.#..",
+    ])]
+    #[case("╬", &[
+        ".#.#..",
+        ".#.#..",
+        "##.###",
+        "......",
+        "##.###",
+        ".#.#..",
+        ".#.#..",
+    ])]
+    #[case("╒", &[
+        "......",
+        "......",
+        "..####",
+        "..#...",
+        "..####",
+        "..#...",
+        "..#...",
+    ])]
+    #[case("▀", &[
+        "######",
+        "######",
+        "######",
+        "######",
+        "......",
+        "......",
+        "......",
+    ])]
+    #[case("▂", &[
+        "......",
+        "......",
+        "......",
+        "......",
+        "......",
+        "######",
+        "######",
+    ])]
+    #[case("▌", &[
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+    ])]
+    #[case("▒", &[
+        "#.#.#.",
+        ".#.#.#",
+        "#.#.#.",
+        ".#.#.#",
+        "#.#.#.",
+        ".#.#.#",
+        "#.#.#.",
+    ])]
+    #[case("⠁", &[
+        "###...",
+        "......",
+        "......",
+        "......",
+        "......",
+        "......",
+        "......",
+    ])]
+    #[case("⢣", &[
+        "###...",
+        "###...",
+        "###...",
+        "...###",
+        "...###",
+        "...###",
+        "...###",
+    ])]
+    #[case("⣿", &[
+        "######",
+        "######",
+        "######",
+        "######",
+        "######",
+        "######",
+        "######",
+    ])]
+    #[case("▚", &[
+        "###...",
+        "###...",
+        "###...",
+        "...###",
+        "...###",
+        "...###",
+        "...###",
+    ])]
+    #[case("\u{1FB00}", &[
+        "###...",
+        "###...",
+        "......",
+        "......",
+        "......",
+        "......",
+        "......",
+    ])]
+    #[case("\u{1FB14}", &[
+        "...###",
+        "...###",
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+        "###...",
+    ])]
+    #[case("\u{1FB3B}", &[
+        "...###",
+        "...###",
+        "######",
+        "######",
+        "######",
+        "######",
+        "######",
+    ])]
+    fn draws_glyph(#[case] symbol: &str, #[case] expected: &[&str]) {
+        render(symbol, Size::new(6, 7)).assert_pattern(expected);
+    }
+
+    #[rstest]
+    #[case("a")]
+    #[case("─a")]
+    #[case("")]
+    fn ignores_unsupported_symbols(#[case] symbol: &str) {
+        let mut display = MockDisplay::<BinaryColor>::new();
+        let area = Rectangle::new(Point::zero(), Size::new(6, 7));
+        let handled = draw(
+            &mut display,
+            symbol,
+            area,
+            BinaryColor::On,
+            BinaryColor::Off,
+        );
+        assert!(!handled.unwrap());
+        assert_eq!(display, MockDisplay::new());
+    }
+
+    fn render_replacement(
+        replacement: ReplacementGlyph,
+        c: char,
+        size: Size,
+    ) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        draw_replacement(
+            &mut display,
+            replacement,
+            c,
+            Rectangle::new(Point::zero(), size),
+            BinaryColor::On,
+            BinaryColor::Off,
+        )
+        .unwrap();
+        display
+    }
+
+    #[rstest]
+    #[case(ReplacementGlyph::HollowBox, 'Ā', Size::new(6, 7), &[
+        "......",
+        ".####.",
+        ".#..#.",
+        ".#..#.",
+        ".#..#.",
+        ".####.",
+        "......",
+    ])]
+    #[case(ReplacementGlyph::HexBox, 'Ā', Size::new(6, 7), &[
+        "......",
+        ".####.",
+        ".#..#.",
+        ".#..#.",
+        ".#..#.",
+        ".####.",
+        "......",
+    ])]
+    #[case(ReplacementGlyph::HexBox, '\u{1AF}', Size::new(11, 15), &[
+        "...........",
+        ".#########.",
+        ".####..#.#.",
+        ".##.#.##.#.",
+        ".##.#..#.#.",
+        ".##.#..#.#.",
+        ".####.####.",
+        ".#.......#.",
+        ".####.####.",
+        ".##.#.#..#.",
+        ".####.####.",
+        ".##.#.#..#.",
+        ".##.#.#..#.",
+        ".#########.",
+        "...........",
+    ])]
+    fn draws_replacement_glyph(
+        #[case] replacement: ReplacementGlyph,
+        #[case] c: char,
+        #[case] size: Size,
+        #[case] expected: &[&str],
+    ) {
+        render_replacement(replacement, c, size).assert_pattern(expected);
+    }
+}
@@ -0,0 +1,811 @@
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::backend::{ReplacementGlyph, UnderlineStyle};
+use crate::cache::{GlyphCache, Mask};
+use crate::colors::*;
+use crate::font::Font;
+use crate::procedural;
+use embedded_graphics::draw_target::{DrawTarget, DrawTargetExt};
+use embedded_graphics::geometry;
+use embedded_graphics::mono_font::DecorationDimensions;
+use embedded_graphics::pixelcolor::{PixelColor, Rgb888};
+use embedded_graphics::primitives::Rectangle;
+use ratatui_core::buffer::Cell;
+use ratatui_core::style;
+use unicode_width::UnicodeWidthStr;
+
+/// Cell rendering settings shared by every display the terminal is drawn to.
+pub(crate) struct Renderer {
+    pub(crate) font_regular: Font,
+    pub(crate) font_bold: Option<Font>,
+    pub(crate) font_italic: Option<Font>,
+    pub(crate) font_bold_italic: Option<Font>,
+    pub(crate) font_wide: Option<Font>,
+    pub(crate) font_fallbacks: Vec<Font>,
+    pub(crate) letter_spacing: u16,
+    pub(crate) line_spacing: u16,
+    pub(crate) margin_color: style::Color,
+    pub(crate) scale: u32,
+    pub(crate) procedural_glyphs: bool,
+    pub(crate) synthetic_bold: bool,
+    pub(crate) brighten_bold: bool,
+    pub(crate) synthetic_italic: bool,
+    pub(crate) replacement_glyph: Option<ReplacementGlyph>,
+    pub(crate) underline_style: UnderlineStyle,
+    pub(crate) underline_thickness: Option<u32>,
+    pub(crate) underline_offset: Option<u32>,
+    pub(crate) glyph_cache: RefCell<GlyphCache>,
+}
+
+impl Renderer {
+    /// Size of a single terminal cell in pixels, including the spacing around the glyph.
+    ///
+    /// Returns `None` if the size overflows.
+    pub(crate) fn cell_size(&self) -> Option<geometry::Size> {
+        let font = self.font_regular.character_size();
+        Some(geometry::Size::new(
+            font.width
+                .checked_mul(self.scale)?
+                .checked_add(self.letter_spacing as u32)?,
+            font.height
+                .checked_mul(self.scale)?
+                .checked_add(self.line_spacing as u32)?,
+        ))
+    }
+
+    /// Draws a single cell with its top left corner at `position`.
+    ///
+    /// The spacing is split evenly around the glyph and filled with the cell background.
+    /// Double-width characters are drawn across the cell and the one to its right.
+    pub(crate) fn draw_cell<T, C>(
+        &self,
+        target: &mut T,
+        position: geometry::Point,
+        cell: &Cell,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Color = C>,
+        C: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor>,
+    {
+        // Cells of an invalid size are never placed on the grid.
+        let Some(cell_size) = self.cell_size() else {
+            return Ok(());
+        };
+        let wide = is_wide(cell);
+        let area = if wide {
+            Rectangle::new(position, cell_size.component_mul(geometry::Size::new(2, 1)))
+        } else {
+            Rectangle::new(position, cell_size)
+        };
+
+        if is_blank(cell) {
+            return target.fill_solid(&area, TermColor(cell.bg, TermColorType::Background).into());
+        }
+
+        // Procedural glyphs fill the whole cell, and only need the decorations drawn over them.
+        let procedural = self.procedural_glyphs
+            && procedural::draw(
+                target,
+                cell.symbol(),
+                area,
+                TermColor(cell.fg, TermColorType::Foreground).into(),
+                TermColor(cell.bg, TermColorType::Background).into(),
+            )?;
+        if procedural
+            && !cell
+                .modifier
+                .intersects(style::Modifier::UNDERLINED | style::Modifier::CROSSED_OUT)
+        {
+            return Ok(());
+        }
+
+        let (font, face) = self.select_font(cell.modifier);
+        let synthetic = cell.modifier - face;
+        let embolden = self.synthetic_bold && synthetic.contains(style::Modifier::BOLD);
+        let slant = self.synthetic_italic && synthetic.contains(style::Modifier::ITALIC);
+        let fg = if embolden && self.brighten_bold {
+            brighten(cell.fg)
+        } else {
+            cell.fg
+        };
+        let fg = TermColor(fg, TermColorType::Foreground).into();
+        let bg = TermColor(cell.bg, TermColorType::Background).into();
+        let glyph_offset =
+            geometry::Point::new(self.letter_spacing as i32 / 2, self.line_spacing as i32 / 2);
+
+        if !procedural && (self.letter_spacing > 0 || self.line_spacing > 0) {
+            target.fill_solid(&area, bg)?;
+        }
+
+        // Area of the cell covered by glyphs of the regular font.
+        let glyph = Rectangle::new(
+            position + glyph_offset,
+            area.size - geometry::Size::new(self.letter_spacing as u32, self.line_spacing as u32),
+        );
+
+        // Combining marks and other characters following the first one of a grapheme cluster
+        // are overlaid on its glyph.
+        let symbol = cell.symbol();
+        let (base, marks) = symbol.split_at(symbol.chars().next().map_or(0, char::len_utf8));
+        let cell_font = font;
+
+        // Characters missing from all fonts are drawn as the configured replacement glyph.
+        let mut replacement = None;
+        let font = match base.chars().next() {
+            Some(c)
+                if !procedural
+                    && (wide
+                        || !self.font_fallbacks.is_empty()
+                        || self.replacement_glyph.is_some()) =>
+            {
+                match self.resolve_font(font, c, wide) {
+                    Some(font) => font,
+                    None => {
+                        replacement = self.replacement_glyph.map(|glyph| (glyph, c));
+                        font
+                    }
+                }
+            }
+            _ => font,
+        };
+
+        // TODO: DIM, SLOW_BLINK, RAPID_BLINK, REVERSED and HIDDEN
+        let underline = cell
+            .modifier
+            .contains(style::Modifier::UNDERLINED)
+            .then(|| match cell.underline_color {
+                style::Color::Reset => fg,
+                color => TermColor(color, TermColorType::Foreground).into(),
+            });
+        let strikethrough = cell
+            .modifier
+            .contains(style::Modifier::CROSSED_OUT)
+            .then_some(fg);
+
+        if let Some((replacement, c)) = replacement {
+            procedural::draw_replacement(target, replacement, c, glyph, fg, bg)?;
+        } else if !procedural {
+            let mut cache = self.glyph_cache.borrow_mut();
+            let mask = cache.mask(symbol, cell.modifier, glyph.size, |mask| {
+                self.rasterize(mask, cell_font, font, base, marks, embolden, slant)
+            });
+            target.fill_contiguous(
+                &glyph,
+                mask.coverage
+                    .iter()
+                    .map(|&alpha| blend(fg, bg, alpha).unwrap_or(bg)),
+            )?;
+        }
+
+        // Decorations span the whole glyph area and aren't slanted, so they join across cells.
+        let mut target = target.clipped(&glyph);
+        let pen = self.pen(glyph, font, false);
+        let pen = Pen {
+            origin: geometry::Point::new(glyph.top_left.x, pen.origin.y),
+            ..pen
+        };
+        let columns = glyph.size.width.div_ceil(self.scale);
+        let rows = font.character_size().height;
+        if let Some(color) = underline {
+            let line = font.underline();
+            let line = DecorationDimensions::new(
+                self.underline_offset.unwrap_or(line.offset),
+                self.underline_thickness.unwrap_or(line.height),
+            );
+            pen.draw_line(
+                &mut target,
+                columns,
+                rows,
+                line,
+                self.underline_style,
+                color,
+            )?;
+        }
+        if let Some(color) = strikethrough {
+            let line = font.strikethrough();
+            pen.draw_line(
+                &mut target,
+                columns,
+                rows,
+                line,
+                UnderlineStyle::Straight,
+                color,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Rasterises the `base` character from `font` and the following `marks` into `mask`,
+    /// covering the area of the cell's glyphs.
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize(
+        &self,
+        mask: &mut Mask,
+        cell_font: &Font,
+        font: &Font,
+        base: &str,
+        marks: &str,
+        embolden: bool,
+        slant: bool,
+    ) {
+        let area = Rectangle::new(geometry::Point::zero(), mask.size);
+        let glyphs = base.chars().map(|c| (c, Some(font))).chain(
+            // Marks missing from the fonts, e.g. joiners and variation selectors, are ignored.
+            marks
+                .chars()
+                .map(|c| (c, self.resolve_font(cell_font, c, false))),
+        );
+        for (c, font) in glyphs {
+            let Some(font) = font else {
+                continue;
+            };
+            let pen = self.pen(area, font, slant);
+            // Bold glyphs are drawn again, one font pixel to the right.
+            let bold_pen = embolden.then(|| pen.shifted(self.scale));
+            for pen in core::iter::once(pen).chain(bold_pen) {
+                pen.rasterize(mask, font, c);
+            }
+        }
+    }
+}
+
+impl Renderer {
+    /// Returns the pen drawing glyphs of `font` into the `glyph` area.
+    ///
+    /// Glyphs of fonts of a different size than the regular font are centered in the cell.
+    fn pen(&self, glyph: Rectangle, font: &Font, slant: bool) -> Pen {
+        let font_size = font.character_size() * self.scale;
+        Pen {
+            origin: glyph.top_left + (geometry::Point::zero() + glyph.size - font_size) / 2,
+            scale: self.scale,
+            slant_baseline: slant.then_some(font.baseline() as i32),
+        }
+    }
+
+    /// Returns the font for a cell with `modifier`, together with the bold and italic
+    /// modifiers its face provides.
+    ///
+    /// Bold italic cells fall back to the bold, then the italic, then the regular font.
+    fn select_font(&self, modifier: style::Modifier) -> (&Font, style::Modifier) {
+        use style::Modifier;
+
+        let bold = modifier.contains(Modifier::BOLD);
+        let italic = modifier.contains(Modifier::ITALIC);
+        match (&self.font_bold_italic, &self.font_bold, &self.font_italic) {
+            (Some(font), _, _) if bold && italic => (font, Modifier::BOLD | Modifier::ITALIC),
+            (_, Some(font), _) if bold => (font, Modifier::BOLD),
+            (_, _, Some(font)) if italic => (font, Modifier::ITALIC),
+            _ => (&self.font_regular, Modifier::empty()),
+        }
+    }
+
+    /// Returns the first font of the fallback chain, starting with `font`, that has a glyph for `c`.
+    ///
+    /// The chain of `wide` characters starts with the double-width font.
+    fn resolve_font<'a>(&'a self, font: &'a Font, c: char, wide: bool) -> Option<&'a Font> {
+        self.font_wide
+            .iter()
+            .filter(|_| wide)
+            .chain(core::iter::once(font))
+            .chain(&self.font_fallbacks)
+            .find(|font| font.has_glyph(c))
+    }
+
+    /// Returns the character of `cell` if none of the fonts have a glyph for it.
+    pub(crate) fn missing_glyph(&self, cell: &Cell) -> Option<char> {
+        let c = cell.symbol().chars().next()?;
+        if self.procedural_glyphs && procedural::is_supported(c) {
+            return None;
+        }
+        match self.resolve_font(self.select_font(cell.modifier).0, c, is_wide(cell)) {
+            Some(_) => None,
+            None => Some(c),
+        }
+    }
+}
+
+/// Returns whether `cell` shows nothing but its background.
+fn is_blank(cell: &Cell) -> bool {
+    matches!(cell.symbol(), "" | " ")
+        && !cell
+            .modifier
+            .intersects(style::Modifier::UNDERLINED | style::Modifier::CROSSED_OUT)
+}
+
+/// Returns whether the character of `cell` takes two cells.
+pub(crate) fn is_wide(cell: &Cell) -> bool {
+    cell.symbol().width() > 1
+}
+
+/// Draws glyphs with their top left corner at `origin`,
+/// magnifying each font pixel into a square of `scale` × `scale` pixels.
+///
+/// If `slant_baseline` is set, every row is shifted horizontally by a third of its distance
+/// above the baseline, i.e. to the right above it and to the left below it.
+#[derive(Clone, Copy)]
+struct Pen {
+    origin: geometry::Point,
+    scale: u32,
+    slant_baseline: Option<i32>,
+}
+
+impl Pen {
+    /// Returns the pen moved `dx` display pixels to the right.
+    fn shifted(self, dx: u32) -> Self {
+        Self {
+            origin: self.origin + geometry::Point::new(dx as i32, 0),
+            ..self
+        }
+    }
+
+    /// Returns the area covered by the font pixel at `point`,
+    /// relative to the top left corner of the glyph.
+    fn pixel_area(&self, point: geometry::Point) -> Rectangle {
+        let shift = self
+            .slant_baseline
+            .map_or(0, |baseline| (baseline - point.y).div_euclid(3));
+        Rectangle::new(
+            self.origin + (point + geometry::Point::new(shift, 0)) * self.scale as i32,
+            geometry::Size::new_equal(self.scale),
+        )
+    }
+
+    /// Rasterises the glyph of `font` for `c` into `mask`.
+    fn rasterize(&self, mask: &mut Mask, font: &Font, c: char) {
+        font.draw_glyph(c, &mut |point, alpha| {
+            mask.paint(self.pixel_area(point), alpha)
+        });
+    }
+
+    /// Draws a decoration line of `style`, such as the underline, across `columns`
+    /// of a glyph `rows` high.
+    ///
+    /// Double lines are separated by a font pixel, and curly lines wave a font pixel
+    /// above and below the line. Lines that don't fit into the glyph are moved up.
+    /// Patterns are aligned to the display, so that they continue across cells.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_line<T: DrawTarget>(
+        &self,
+        target: &mut T,
+        columns: u32,
+        rows: u32,
+        line: DecorationDimensions,
+        style: UnderlineStyle,
+        color: T::Color,
+    ) -> Result<(), T::Error> {
+        let thickness = line.height.max(1);
+        let (top, height) = match style {
+            UnderlineStyle::Double => (line.offset, 2 * thickness + 1),
+            UnderlineStyle::Curly => (line.offset.saturating_sub(1), thickness + 2),
+            _ => (line.offset, thickness),
+        };
+        let top = top.min(rows.saturating_sub(height));
+        let first_column = self.origin.x.div_euclid(self.scale as i32);
+
+        let thickness = thickness as i32;
+        for y in 0..height as i32 {
+            for x in 0..columns as i32 {
+                let column = first_column + x;
+                let covered = match style {
+                    UnderlineStyle::Straight => true,
+                    UnderlineStyle::Double => y != thickness,
+                    UnderlineStyle::Dotted => column.div_euclid(thickness).rem_euclid(2) == 0,
+                    UnderlineStyle::Dashed => column.rem_euclid(5 * thickness) < 3 * thickness,
+                    UnderlineStyle::Curly => {
+                        let wave = [1, 0, 1, 2][column.rem_euclid(4) as usize];
+                        (wave..wave + thickness).contains(&y)
+                    }
+                };
+                if covered {
+                    let point = geometry::Point::new(x, top as i32 + y);
+                    target.fill_solid(&self.pixel_area(point), color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::mono_font::{ascii, iso_8859_1};
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    fn renderer() -> Renderer {
+        Renderer {
+            font_regular: ascii::FONT_6X10.into(),
+            font_bold: None,
+            font_italic: None,
+            font_bold_italic: None,
+            font_wide: None,
+            font_fallbacks: Vec::new(),
+            letter_spacing: 0,
+            line_spacing: 0,
+            margin_color: style::Color::Reset,
+            scale: 1,
+            procedural_glyphs: true,
+            synthetic_bold: false,
+            brighten_bold: false,
+            synthetic_italic: false,
+            replacement_glyph: None,
+            underline_style: UnderlineStyle::Straight,
+            underline_thickness: None,
+            underline_offset: None,
+            glyph_cache: RefCell::new(GlyphCache::new(0)),
+        }
+    }
+
+    fn render(renderer: &Renderer, cell: &Cell) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        renderer
+            .draw_cell(&mut display, geometry::Point::zero(), cell)
+            .unwrap();
+        display
+    }
+
+    #[rstest]
+    fn synthetic_bold_thickens_glyph() {
+        let mut cell = Cell::new("l");
+        cell.set_style(style::Modifier::BOLD);
+        let renderer = Renderer {
+            synthetic_bold: true,
+            ..renderer()
+        };
+
+        render(&renderer, &cell).assert_pattern(&[
+            "......", ".###..", "..##..", "..##..", "..##..", "..##..", "..##..", ".####.",
+            "......", "......",
+        ]);
+    }
+
+    #[rstest]
+    fn synthetic_bold_requires_missing_bold_font() {
+        let mut cell = Cell::new("l");
+        cell.set_style(style::Modifier::BOLD);
+        let renderer = Renderer {
+            synthetic_bold: true,
+            font_bold: Some(ascii::FONT_6X10.into()),
+            ..renderer()
+        };
+
+        assert_eq!(render(&renderer, &cell), render(&renderer, &Cell::new("l")));
+    }
+
+    #[rstest]
+    fn synthetic_italic_shears_glyph() {
+        let mut cell = Cell::new("l");
+        cell.set_style(style::Modifier::ITALIC);
+        let renderer = Renderer {
+            synthetic_italic: true,
+            ..renderer()
+        };
+
+        render(&renderer, &cell).assert_pattern(&[
+            "......", "...##.", "...#..", "...#..", "...#..", "..#...", "..#...", ".###..",
+            "......", "......",
+        ]);
+    }
+
+    #[rstest]
+    fn procedural_glyph_is_crossed_out() {
+        let mut cell = Cell::new("▀");
+        cell.set_style(style::Modifier::CROSSED_OUT);
+
+        render(&renderer(), &cell).assert_pattern(&[
+            "######", "######", "######", "######", "######", "######", "......", "......",
+            "......", "......",
+        ]);
+    }
+
+    #[rstest]
+    fn procedural_glyph_is_underlined() {
+        let mut cell = Cell::new("▀");
+        cell.set_style(style::Modifier::UNDERLINED);
+
+        render(&renderer(), &cell).assert_pattern(&[
+            "######", "######", "######", "######", "######", "......", "......", "......",
+            "......", "######",
+        ]);
+    }
+
+    #[rstest]
+    fn replacement_glyph_is_decorated() {
+        let mut cell = Cell::new("€");
+        cell.set_style(style::Modifier::UNDERLINED | style::Modifier::CROSSED_OUT);
+        let renderer = Renderer {
+            replacement_glyph: Some(ReplacementGlyph::HollowBox),
+            ..renderer()
+        };
+
+        render(&renderer, &cell).assert_pattern(&[
+            "......", ".####.", ".#..#.", ".#..#.", ".#..#.", "######", ".#..#.", ".#..#.",
+            ".####.", "######",
+        ]);
+    }
+
+    #[rstest]
+    fn synthetic_italic_underline_joins_across_cells() {
+        let mut cell = Cell::new("a");
+        cell.set_style(style::Modifier::ITALIC | style::Modifier::UNDERLINED);
+        let renderer = Renderer {
+            synthetic_italic: true,
+            ..renderer()
+        };
+
+        let mut display = MockDisplay::<BinaryColor>::new();
+        display.set_allow_overdraw(true);
+        for x in 0..3 {
+            renderer
+                .draw_cell(&mut display, geometry::Point::new(x * 6, 0), &cell)
+                .unwrap();
+        }
+        let underline: alloc::string::String = (0..18)
+            .map(|x| match display.get_pixel(geometry::Point::new(x, 9)) {
+                Some(BinaryColor::On) => '#',
+                _ => '.',
+            })
+            .collect();
+        assert_eq!(underline, "##################");
+    }
+
+    #[rstest]
+    #[case(style::Modifier::empty(), None, None, None, 0)]
+    #[case(style::Modifier::BOLD, Some(1), Some(2), Some(3), 1)]
+    #[case(style::Modifier::BOLD, None, Some(2), Some(3), 0)]
+    #[case(style::Modifier::ITALIC, Some(1), Some(2), Some(3), 2)]
+    #[case(style::Modifier::BOLD | style::Modifier::ITALIC, Some(1), Some(2), Some(3), 3)]
+    #[case(style::Modifier::BOLD | style::Modifier::ITALIC, Some(1), Some(2), None, 1)]
+    #[case(style::Modifier::BOLD | style::Modifier::ITALIC, None, Some(2), None, 2)]
+    #[case(style::Modifier::BOLD | style::Modifier::ITALIC, None, None, None, 0)]
+    fn select_font_falls_back(
+        #[case] modifier: style::Modifier,
+        #[case] bold: Option<usize>,
+        #[case] italic: Option<usize>,
+        #[case] bold_italic: Option<usize>,
+        #[case] expected: usize,
+    ) {
+        // Fonts are told apart by their size.
+        let fonts = [
+            ascii::FONT_6X10,
+            ascii::FONT_4X6,
+            ascii::FONT_5X7,
+            ascii::FONT_5X8,
+        ];
+        let font = |index: usize| Font::from(fonts[index]);
+        let renderer = Renderer {
+            font_bold: bold.map(font),
+            font_italic: italic.map(font),
+            font_bold_italic: bold_italic.map(font),
+            ..renderer()
+        };
+
+        let (selected, _) = renderer.select_font(modifier);
+        assert_eq!(selected.character_size(), fonts[expected].character_size);
+    }
+
+    #[rstest]
+    fn cached_glyphs_match_rasterised_ones() {
+        let mut cell = Cell::new("l");
+        cell.set_style(style::Modifier::BOLD | style::Modifier::UNDERLINED);
+        let renderer = Renderer {
+            synthetic_bold: true,
+            ..renderer()
+        };
+        let expected = render(&renderer, &cell);
+        let renderer = Renderer {
+            glyph_cache: RefCell::new(GlyphCache::new(1024)),
+            ..renderer
+        };
+
+        assert_eq!(render(&renderer, &cell), expected);
+        assert_eq!(render(&renderer, &cell), expected);
+    }
+
+    #[rstest]
+    #[case(" ", style::Modifier::BOLD, true)]
+    #[case("", style::Modifier::empty(), true)]
+    #[case(" ", style::Modifier::UNDERLINED, false)]
+    #[case(" ", style::Modifier::CROSSED_OUT, false)]
+    #[case("a", style::Modifier::empty(), false)]
+    fn blank_cells_are_filled(
+        #[case] symbol: &'static str,
+        #[case] modifier: style::Modifier,
+        #[case] blank: bool,
+    ) {
+        let mut cell = Cell::new(symbol);
+        cell.set_style(modifier).set_bg(style::Color::White);
+        let renderer = Renderer {
+            letter_spacing: 2,
+            line_spacing: 1,
+            ..renderer()
+        };
+
+        let display = render(&renderer, &cell);
+        assert_eq!(is_blank(&cell), blank);
+        if blank {
+            let mut expected = MockDisplay::new();
+            expected
+                .fill_solid(
+                    &Rectangle::new(geometry::Point::zero(), geometry::Size::new(8, 11)),
+                    BinaryColor::On,
+                )
+                .unwrap();
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[rstest]
+    #[case(UnderlineStyle::Straight, None, None, ["......", "......", "######"])]
+    #[case(UnderlineStyle::Double, None, None, ["######", "......", "######"])]
+    #[case(UnderlineStyle::Curly, None, None, [".#...#", "#.#.#.", "...#.."])]
+    #[case(UnderlineStyle::Dotted, None, None, ["......", "......", "#.#.#."])]
+    #[case(UnderlineStyle::Dashed, None, None, ["......", "......", "###..#"])]
+    #[case(UnderlineStyle::Straight, Some(2), Some(7), ["######", "######", "......"])]
+    #[case(UnderlineStyle::Dotted, Some(2), None, ["......", "##..##", "##..##"])]
+    fn draws_underline_styles(
+        #[case] underline_style: UnderlineStyle,
+        #[case] underline_thickness: Option<u32>,
+        #[case] underline_offset: Option<u32>,
+        #[case] expected: [&str; 3],
+    ) {
+        let mut cell = Cell::new(" ");
+        cell.set_style(style::Modifier::UNDERLINED);
+        let renderer = Renderer {
+            underline_style,
+            underline_thickness,
+            underline_offset,
+            ..renderer()
+        };
+
+        let blank = "......";
+        let mut pattern = [blank; 10];
+        pattern[7..].copy_from_slice(&expected);
+        render(&renderer, &cell).assert_pattern(&pattern);
+    }
+
+    #[rstest]
+    fn underline_color_requires_underlined_modifier() {
+        let mut cell = Cell::new("l");
+        cell.underline_color = style::Color::White;
+        assert_eq!(
+            render(&renderer(), &cell),
+            render(&renderer(), &Cell::new("l"))
+        );
+    }
+
+    #[rstest]
+    fn smaller_font_is_centered() {
+        let mut cell = Cell::new("l");
+        cell.set_style(style::Modifier::BOLD);
+        let renderer = Renderer {
+            font_bold: Some(ascii::FONT_4X6.into()),
+            ..renderer()
+        };
+
+        render(&renderer, &cell).assert_pattern(&[
+            "......", "......", ".##...", "..#...", "..#...", "..#...", ".###..", "......",
+            "......", "......",
+        ]);
+    }
+
+    #[rstest]
+    fn larger_font_is_clipped_to_cell() {
+        let mut cell = Cell::new("W");
+        cell.set_style(style::Modifier::BOLD);
+        let renderer = Renderer {
+            font_bold: Some(ascii::FONT_10X20.into()),
+            ..renderer()
+        };
+
+        let display = render(&renderer, &cell);
+        assert_eq!(
+            display.affected_area(),
+            Rectangle::new(geometry::Point::zero(), geometry::Size::new(6, 10))
+        );
+    }
+
+    #[rstest]
+    #[case('é', true, Some(13))]
+    #[case('é', false, None)]
+    #[case('a', true, Some(13))]
+    #[case('a', false, Some(10))]
+    fn resolve_font_prefers_wide_font(
+        #[case] c: char,
+        #[case] wide: bool,
+        #[case] expected: Option<u32>,
+    ) {
+        let renderer = Renderer {
+            font_wide: Some(iso_8859_1::FONT_6X13.into()),
+            ..renderer()
+        };
+
+        let font = renderer.resolve_font(&renderer.font_regular, c, wide);
+        assert_eq!(font.map(|font| font.character_size().height), expected);
+    }
+
+    #[rstest]
+    fn wide_cell_spans_two_cells() {
+        let mut cell = Cell::new("日");
+        cell.set_bg(style::Color::White);
+
+        let display = render(&renderer(), &cell);
+        assert_eq!(
+            display.affected_area(),
+            Rectangle::new(geometry::Point::zero(), geometry::Size::new(12, 10))
+        );
+    }
+
+    #[rstest]
+    fn blends_anti_aliased_glyphs() {
+        use crate::font::AlphaFont;
+        use embedded_graphics::mono_font::DecorationDimensions;
+        use embedded_graphics::mono_font::mapping::StrGlyphMapping;
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        static MAPPING: StrGlyphMapping = StrGlyphMapping::new("a", 0);
+        let font = AlphaFont::new(
+            &[0b1001_0000],
+            2,
+            2,
+            &MAPPING,
+            geometry::Size::new(2, 1),
+            0,
+            DecorationDimensions::new(0, 1),
+            DecorationDimensions::new(0, 1),
+        );
+        let renderer = Renderer {
+            font_regular: font.into(),
+            ..renderer()
+        };
+        let mut cell = Cell::new("a");
+        cell.set_fg(style::Color::Rgb(255, 255, 0))
+            .set_bg(style::Color::Rgb(0, 0, 255));
+
+        let mut display = MockDisplay::<Rgb888>::new();
+        display.set_allow_overdraw(true);
+        renderer
+            .draw_cell(&mut display, geometry::Point::zero(), &cell)
+            .unwrap();
+        assert_eq!(
+            [0, 1].map(|x| display.get_pixel(geometry::Point::new(x, 0))),
+            [
+                Some(Rgb888::new(170, 170, 85)),
+                Some(Rgb888::new(85, 85, 170))
+            ]
+        );
+    }
+
+    #[cfg(feature = "fonts")]
+    #[rstest]
+    fn overlays_combining_marks() {
+        let renderer = Renderer {
+            font_regular: crate::fonts::MONO_6X10.into(),
+            ..renderer()
+        };
+
+        render(&renderer, &Cell::new("e\u{301}")).assert_pattern(&[
+            "...#..", "..#...", "......", ".###..", "#...#.", "#####.", "#.....", ".###..",
+            "......", "......",
+        ]);
+    }
+
+    #[cfg(feature = "fonts")]
+    #[rstest]
+    fn ignores_marks_missing_from_fonts() {
+        let renderer = Renderer {
+            font_regular: crate::fonts::MONO_6X10.into(),
+            ..renderer()
+        };
+
+        assert_eq!(
+            render(&renderer, &Cell::new("e\u{200D}")),
+            render(&renderer, &Cell::new("e"))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::backend::{FlushCallback, Padding, TerminalAlignment};
+use crate::colors::*;
+use crate::error::Error;
+use crate::framebuffer;
+use crate::render::Renderer;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{self, Dimensions};
+use embedded_graphics::pixelcolor::{PixelColor, Rgb888};
+use embedded_graphics::primitives::Rectangle;
+use ratatui_core::buffer::Cell;
+use ratatui_core::layout;
+use ratatui_core::style;
+
+type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// A display the terminal is rendered to.
+///
+/// Implemented by [`Target`], so that displays with different color types
+/// can be driven by a single backend.
+pub(crate) trait Output {
+    /// Size of the display in pixels.
+    fn pixels(&self) -> layout::Size;
+
+    /// Size of the display area available to the terminal grid, in pixels.
+    fn area(&self) -> layout::Size;
+
+    /// Places a grid of `columns_rows` cells, each of `cell_size` pixels, on the display.
+    fn set_grid(&mut self, columns_rows: layout::Size, cell_size: geometry::Size);
+
+    /// Draws the cell at column `x` and row `y`.
+    fn draw_cell(&mut self, renderer: &Renderer, x: u16, y: u16, cell: &Cell) -> Result<()>;
+
+    /// Clears the framebuffer.
+    fn clear(&mut self) -> Result<()>;
+
+    /// Clears the cells of the grid in `area`.
+    fn clear_cells(&mut self, area: layout::Rect) -> Result<()>;
+
+    /// Paints the margins around the grid and sends the framebuffer to the display.
+    fn flush(&mut self, renderer: &Renderer) -> Result<()>;
+}
+
+/// A display together with its framebuffer, flush callback and alignment.
+pub(crate) struct Target<'display, D, C>
+where
+    D: DrawTarget<Color = C> + 'display,
+    C: PixelColor + 'display,
+{
+    display: &'display mut D,
+    flush_callback: FlushCallback<D>,
+    buffer: framebuffer::HeapBuffer<C>,
+
+    padding: Padding,
+    vertical_alignment: TerminalAlignment,
+    horizontal_alignment: TerminalAlignment,
+
+    cell_size: geometry::Size,
+    char_offset: geometry::Point,
+    grid: Rectangle,
+}
+
+impl<'display, D, C> Target<'display, D, C>
+where
+    D: DrawTarget<Color = C> + 'static,
+    C: PixelColor + From<TermColor> + 'static,
+{
+    pub(crate) fn new(
+        display: &'display mut D,
+        flush_callback: FlushCallback<D>,
+        padding: Padding,
+        vertical_alignment: TerminalAlignment,
+        horizontal_alignment: TerminalAlignment,
+    ) -> Self {
+        Self {
+            buffer: framebuffer::HeapBuffer::new(display.bounding_box()),
+            display,
+            flush_callback,
+            padding,
+            vertical_alignment,
+            horizontal_alignment,
+            cell_size: geometry::Size::zero(),
+            char_offset: geometry::Point::zero(),
+            grid: Rectangle::zero(),
+        }
+    }
+
+    pub(crate) fn set_alignment(
+        &mut self,
+        vertical_alignment: TerminalAlignment,
+        horizontal_alignment: TerminalAlignment,
+    ) {
+        self.vertical_alignment = vertical_alignment;
+        self.horizontal_alignment = horizontal_alignment;
+    }
+
+    /// Fills the area between the edges of the display and the grid.
+    fn paint_margins(&mut self, color: C) -> Result<()> {
+        let display = self.buffer.bounding_box();
+        // Padding larger than the display leaves an empty grid outside of it.
+        let grid = self.grid.intersection(&display);
+        let (left, top) = (grid.top_left.x as u32, grid.top_left.y as u32);
+        let right = display.size.width - left - grid.size.width;
+        let bottom = display.size.height - top - grid.size.height;
+
+        let margins = [
+            Rectangle::new(
+                geometry::Point::zero(),
+                geometry::Size::new(display.size.width, top),
+            ),
+            Rectangle::new(
+                geometry::Point::new(0, (top + grid.size.height) as i32),
+                geometry::Size::new(display.size.width, bottom),
+            ),
+            Rectangle::new(
+                geometry::Point::new(0, top as i32),
+                geometry::Size::new(left, grid.size.height),
+            ),
+            Rectangle::new(
+                geometry::Point::new((left + grid.size.width) as i32, top as i32),
+                geometry::Size::new(right, grid.size.height),
+            ),
+        ];
+        for margin in &margins {
+            self.buffer.fill_solid(margin, color).map_err(Error::draw)?;
+        }
+        Ok(())
+    }
+}
+
+impl<D, C> Output for Target<'_, D, C>
+where
+    D: DrawTarget<Color = C> + 'static,
+    D::Error: Send + Sync,
+    C: PixelColor + Into<Rgb888> + From<Rgb888> + From<TermColor> + 'static,
+{
+    fn pixels(&self) -> layout::Size {
+        layout::Size {
+            width: self.display.bounding_box().size.width as u16,
+            height: self.display.bounding_box().size.height as u16,
+        }
+    }
+
+    fn area(&self) -> layout::Size {
+        let pixels = self.pixels();
+        layout::Size {
+            width: pixels
+                .width
+                .saturating_sub(self.padding.left.saturating_add(self.padding.right)),
+            height: pixels
+                .height
+                .saturating_sub(self.padding.top.saturating_add(self.padding.bottom)),
+        }
+    }
+
+    fn set_grid(&mut self, columns_rows: layout::Size, cell_size: geometry::Size) {
+        let area = self.area();
+
+        let extra_x = area.width as u32 - columns_rows.width as u32 * cell_size.width;
+        let extra_y = area.height as u32 - columns_rows.height as u32 * cell_size.height;
+
+        let off_x = match self.horizontal_alignment {
+            TerminalAlignment::Start => 0,
+            TerminalAlignment::Center => extra_x / 2, //best effort, might be 1/2 pixel off
+            TerminalAlignment::End => extra_x,
+        } as i32;
+        let off_y = match self.vertical_alignment {
+            TerminalAlignment::Start => 0,
+            TerminalAlignment::Center => extra_y / 2, //best effort, might be 1/2 pixel off
+            TerminalAlignment::End => extra_y,
+        } as i32;
+
+        self.cell_size = cell_size;
+        self.char_offset = geometry::Point::new(
+            self.padding.left as i32 + off_x,
+            self.padding.top as i32 + off_y,
+        );
+        self.grid = Rectangle::new(
+            self.char_offset,
+            geometry::Size::new(
+                columns_rows.width as u32 * cell_size.width,
+                columns_rows.height as u32 * cell_size.height,
+            ),
+        );
+    }
+
+    fn draw_cell(&mut self, renderer: &Renderer, x: u16, y: u16, cell: &Cell) -> Result<()> {
+        let position = geometry::Point::new(
+            x as i32 * self.cell_size.width as i32,
+            y as i32 * self.cell_size.height as i32,
+        );
+
+        renderer
+            .draw_cell(&mut self.buffer, position + self.char_offset, cell)
+            .map_err(Error::draw)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.buffer
+            .clear(TermColor(style::Color::Reset, TermColorType::Background).into())
+            .map_err(Error::draw)
+    }
+
+    fn clear_cells(&mut self, area: layout::Rect) -> Result<()> {
+        let cells = Rectangle::new(
+            self.char_offset
+                + geometry::Point::new(
+                    area.x as i32 * self.cell_size.width as i32,
+                    area.y as i32 * self.cell_size.height as i32,
+                ),
+            geometry::Size::new(
+                area.width as u32 * self.cell_size.width,
+                area.height as u32 * self.cell_size.height,
+            ),
+        );
+        self.buffer
+            .fill_solid(
+                &cells,
+                TermColor(style::Color::Reset, TermColorType::Background).into(),
+            )
+            .map_err(Error::draw)
+    }
+
+    fn flush(&mut self, renderer: &Renderer) -> Result<()> {
+        self.paint_margins(TermColor(renderer.margin_color, TermColorType::Background).into())?;
+        self.display
+            .fill_contiguous(&self.display.bounding_box(), &self.buffer)
+            .map_err(Error::draw)?;
+        (self.flush_callback)(self.display).map_err(Error::FlushError)
+    }
+}